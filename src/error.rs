@@ -0,0 +1,271 @@
+use pinocchio::program_error::ProgramError;
+use thiserror::Error;
+
+/// Program errors, surfaced as `ProgramError::Custom(code)`. Codes are stable: never
+/// renumber or reuse a variant, only append. Structural failures (missing accounts or
+/// signatures, wrong program ids, malformed instruction bytes, overflow) keep the
+/// standard `ProgramError` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[repr(u32)]
+pub enum TreasuryError {
+    #[error("Unknown instruction")]
+    UnknownInstruction = 2000,
+    #[error("Voting deadline has passed")]
+    VotingDeadlinePassed = 2001,
+    #[error("Member has already voted on this proposal")]
+    AlreadyVoted = 2002,
+    #[error("Proposal is not open for voting")]
+    ProposalNotActive = 2003,
+    #[error("Multisig vault is not active")]
+    MultisigInactive = 2004,
+    #[error("Signer is not a multisig member")]
+    NotMultisigMember = 2005,
+    #[error("Payout would take the treasury below its minimum balance")]
+    MinimumBalanceBreached = 2006,
+    #[error("Multisig account does not match its PDA")]
+    InvalidMultisigAccount = 2007,
+    #[error("Treasury vault does not belong to this multisig")]
+    InvalidTreasuryVault = 2008,
+    #[error("Proposal account does not match its PDA")]
+    InvalidProposalAccount = 2009,
+    #[error("Proposal belongs to a different multisig")]
+    MultisigIdMismatch = 2010,
+    #[error("Vote type must be 0 (approve) or 1 (reject)")]
+    InvalidVoteType = 2011,
+    #[error("No vote slots left on this proposal")]
+    VoteLimitReached = 2012,
+    #[error("Signer is not the multisig admin")]
+    NotAdmin = 2013,
+    #[error("Admin cannot be added as a regular member")]
+    AdminCannotBeMember = 2014,
+    #[error("Multisig member limit reached")]
+    MemberLimitReached = 2015,
+    #[error("Member key cannot be the default address")]
+    InvalidMemberKey = 2016,
+    #[error("Member already exists")]
+    MemberAlreadyExists = 2017,
+    #[error("Member is not part of the multisig")]
+    MemberNotFound = 2018,
+    #[error("Threshold must be between 1 and the member count")]
+    InvalidThreshold = 2019,
+    #[error("Proposal expiry must be positive")]
+    InvalidProposalExpiry = 2020,
+    #[error("Voting deadline must be in the future")]
+    InvalidVotingDeadline = 2021,
+    #[error("Proposal name and description are required")]
+    InvalidProposalMetadata = 2022,
+    #[error("Proposal payload does not match the stream type")]
+    InvalidProposalPayload = 2023,
+    #[error("Proposal type does not match the instruction")]
+    ProposalTypeMismatch = 2024,
+    #[error("Proposal is not approved or its voting window has closed")]
+    ProposalNotExecutable = 2025,
+    #[error("Executor did not approve this proposal")]
+    ExecutorDidNotApprove = 2026,
+    #[error("Proposal must be executed through its dedicated instruction")]
+    DedicatedInstructionRequired = 2027,
+    #[error("Execution parameters are bound to the proposal and cannot be supplied")]
+    UnexpectedInstructionData = 2028,
+    #[error("Mint does not match the approved proposal")]
+    MintMismatch = 2029,
+    #[error("Mint is not owned by the token program")]
+    InvalidMint = 2030,
+    #[error("Token account owner or mint does not match")]
+    TokenAccountMismatch = 2031,
+    #[error("Account does not match the approved proposal")]
+    ProposalAccountMismatch = 2032,
+    #[error("Amount must be greater than zero")]
+    InvalidAmount = 2033,
+    #[error("Schedule start must precede its end")]
+    InvalidSchedule = 2034,
+    #[error("Payment stream is not active")]
+    StreamInactive = 2035,
+    #[error("Payment stream has not started")]
+    StreamNotStarted = 2036,
+    #[error("Nothing available to withdraw")]
+    NothingToWithdraw = 2037,
+    #[error("Signer is not a party to this payment stream")]
+    UnauthorizedStreamParty = 2038,
+    #[error("Payment stream account does not match its PDA")]
+    InvalidStreamAccount = 2039,
+    #[error("Vesting contract is not active")]
+    VestingInactive = 2040,
+    #[error("Vesting schedule has not started")]
+    VestingNotStarted = 2041,
+    #[error("Vesting cliff has not been reached")]
+    CliffNotReached = 2042,
+    #[error("Vesting account does not match its PDA")]
+    InvalidVestingAccount = 2043,
+    #[error("Signer is not the vesting contract creator")]
+    NotVestingCreator = 2044,
+    #[error("Batch account does not match its PDA")]
+    InvalidBatchAccount = 2045,
+    #[error("Recipient account missing from the batch")]
+    BatchRecipientMissing = 2046,
+    #[error("Invalid multisig configuration")]
+    InvalidConfig = 2047,
+    #[error("Unknown stream type")]
+    InvalidStreamType = 2048,
+    #[error("Unknown proposal status")]
+    InvalidProposalStatus = 2049,
+    #[error("Account is not of the expected type")]
+    InvalidAccountDiscriminator = 2050,
+    #[error("Account layout version is not supported; migrate the account")]
+    UnsupportedAccountVersion = 2051,
+    #[error("Account is already at the current layout version")]
+    AccountUpToDate = 2052,
+    #[error("Member voting weight must be greater than zero")]
+    InvalidMemberWeight = 2053,
+    #[error("Member does not hold the role this action requires")]
+    MissingMemberRole = 2054,
+    #[error("Member roles contain unknown flags")]
+    InvalidMemberRoles = 2055,
+    #[error("Proposal timelock has not elapsed")]
+    TimelockNotElapsed = 2056,
+    #[error("Only the admin, or the proposer before any votes or with majority consent, can cancel this proposal")]
+    CancellationNotAllowed = 2057,
+    #[error("Proposal has already been executed, rejected or cancelled")]
+    ProposalFinalized = 2058,
+    #[error("Account is not the proposal's proposer")]
+    ProposerMismatch = 2059,
+    #[error("Member has not voted on this proposal")]
+    VoteNotFound = 2060,
+    #[error("Proposal voting window has not ended")]
+    ProposalNotExpired = 2061,
+    #[error("Proposal is still open and cannot be closed")]
+    ProposalStillOpen = 2062,
+    #[error("Only the guardian or enough members can pause the vault")]
+    PauseNotAllowed = 2063,
+    #[error("Vault is not paused")]
+    VaultNotPaused = 2064,
+    #[error("Signer is not the pending admin")]
+    NotPendingAdmin = 2065,
+    #[error("Depositor is not on the vault's deposit allowlist")]
+    DepositorNotAllowed = 2066,
+    #[error("Invalid deposit receipt account")]
+    InvalidDepositReceipt = 2067,
+    #[error("The change would leave no member holding the proposer or executor role")]
+    MissingRoleHolder = 2068,
+}
+
+impl From<TreasuryError> for ProgramError {
+    fn from(error: TreasuryError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+impl TryFrom<u32> for TreasuryError {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        match code {
+            2000 => Ok(TreasuryError::UnknownInstruction),
+            2001 => Ok(TreasuryError::VotingDeadlinePassed),
+            2002 => Ok(TreasuryError::AlreadyVoted),
+            2003 => Ok(TreasuryError::ProposalNotActive),
+            2004 => Ok(TreasuryError::MultisigInactive),
+            2005 => Ok(TreasuryError::NotMultisigMember),
+            2006 => Ok(TreasuryError::MinimumBalanceBreached),
+            2007 => Ok(TreasuryError::InvalidMultisigAccount),
+            2008 => Ok(TreasuryError::InvalidTreasuryVault),
+            2009 => Ok(TreasuryError::InvalidProposalAccount),
+            2010 => Ok(TreasuryError::MultisigIdMismatch),
+            2011 => Ok(TreasuryError::InvalidVoteType),
+            2012 => Ok(TreasuryError::VoteLimitReached),
+            2013 => Ok(TreasuryError::NotAdmin),
+            2014 => Ok(TreasuryError::AdminCannotBeMember),
+            2015 => Ok(TreasuryError::MemberLimitReached),
+            2016 => Ok(TreasuryError::InvalidMemberKey),
+            2017 => Ok(TreasuryError::MemberAlreadyExists),
+            2018 => Ok(TreasuryError::MemberNotFound),
+            2019 => Ok(TreasuryError::InvalidThreshold),
+            2020 => Ok(TreasuryError::InvalidProposalExpiry),
+            2021 => Ok(TreasuryError::InvalidVotingDeadline),
+            2022 => Ok(TreasuryError::InvalidProposalMetadata),
+            2023 => Ok(TreasuryError::InvalidProposalPayload),
+            2024 => Ok(TreasuryError::ProposalTypeMismatch),
+            2025 => Ok(TreasuryError::ProposalNotExecutable),
+            2026 => Ok(TreasuryError::ExecutorDidNotApprove),
+            2027 => Ok(TreasuryError::DedicatedInstructionRequired),
+            2028 => Ok(TreasuryError::UnexpectedInstructionData),
+            2029 => Ok(TreasuryError::MintMismatch),
+            2030 => Ok(TreasuryError::InvalidMint),
+            2031 => Ok(TreasuryError::TokenAccountMismatch),
+            2032 => Ok(TreasuryError::ProposalAccountMismatch),
+            2033 => Ok(TreasuryError::InvalidAmount),
+            2034 => Ok(TreasuryError::InvalidSchedule),
+            2035 => Ok(TreasuryError::StreamInactive),
+            2036 => Ok(TreasuryError::StreamNotStarted),
+            2037 => Ok(TreasuryError::NothingToWithdraw),
+            2038 => Ok(TreasuryError::UnauthorizedStreamParty),
+            2039 => Ok(TreasuryError::InvalidStreamAccount),
+            2040 => Ok(TreasuryError::VestingInactive),
+            2041 => Ok(TreasuryError::VestingNotStarted),
+            2042 => Ok(TreasuryError::CliffNotReached),
+            2043 => Ok(TreasuryError::InvalidVestingAccount),
+            2044 => Ok(TreasuryError::NotVestingCreator),
+            2045 => Ok(TreasuryError::InvalidBatchAccount),
+            2046 => Ok(TreasuryError::BatchRecipientMissing),
+            2047 => Ok(TreasuryError::InvalidConfig),
+            2048 => Ok(TreasuryError::InvalidStreamType),
+            2049 => Ok(TreasuryError::InvalidProposalStatus),
+            2050 => Ok(TreasuryError::InvalidAccountDiscriminator),
+            2051 => Ok(TreasuryError::UnsupportedAccountVersion),
+            2052 => Ok(TreasuryError::AccountUpToDate),
+            2053 => Ok(TreasuryError::InvalidMemberWeight),
+            2054 => Ok(TreasuryError::MissingMemberRole),
+            2055 => Ok(TreasuryError::InvalidMemberRoles),
+            2056 => Ok(TreasuryError::TimelockNotElapsed),
+            2057 => Ok(TreasuryError::CancellationNotAllowed),
+            2058 => Ok(TreasuryError::ProposalFinalized),
+            2059 => Ok(TreasuryError::ProposerMismatch),
+            2060 => Ok(TreasuryError::VoteNotFound),
+            2061 => Ok(TreasuryError::ProposalNotExpired),
+            2062 => Ok(TreasuryError::ProposalStillOpen),
+            2063 => Ok(TreasuryError::PauseNotAllowed),
+            2064 => Ok(TreasuryError::VaultNotPaused),
+            2065 => Ok(TreasuryError::NotPendingAdmin),
+            2066 => Ok(TreasuryError::DepositorNotAllowed),
+            2067 => Ok(TreasuryError::InvalidDepositReceipt),
+            2068 => Ok(TreasuryError::MissingRoleHolder),
+            _ => Err(ProgramError::InvalidArgument)
+        }
+    }
+}
+
+impl TreasuryError {
+    /// Client-side decoder: maps a program error returned by a transaction back to a
+    /// `TreasuryError`, or `None` for standard `ProgramError` variants and foreign codes.
+    pub fn decode(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::try_from(*code).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_roundtrip() {
+        for code in 2000..=2068 {
+            let error = TreasuryError::try_from(code).unwrap();
+            assert_eq!(error as u32, code);
+            assert_eq!(TreasuryError::decode(&error.into()), Some(error));
+        }
+
+        assert!(TreasuryError::try_from(2069).is_err());
+        assert_eq!(TreasuryError::decode(&ProgramError::InvalidAccountData), None);
+    }
+
+    #[test]
+    fn test_legacy_vote_codes_are_stable() {
+        assert_eq!(ProgramError::from(TreasuryError::VotingDeadlinePassed), ProgramError::Custom(2001));
+        assert_eq!(ProgramError::from(TreasuryError::AlreadyVoted), ProgramError::Custom(2002));
+        assert_eq!(ProgramError::from(TreasuryError::ProposalNotActive), ProgramError::Custom(2003));
+        assert_eq!(ProgramError::from(TreasuryError::MultisigInactive), ProgramError::Custom(2004));
+        assert_eq!(ProgramError::from(TreasuryError::NotMultisigMember), ProgramError::Custom(2005));
+    }
+}
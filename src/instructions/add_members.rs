@@ -1,86 +1,122 @@
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
 use pinocchio_log::log;
 
-use crate::states::MultiSignatureVault;
+use crate::{error::TreasuryError, pda, instructions::execute_proposal::{mark_proposal_executed, verify_governed_proposal}, states::{Member, MultiSignatureVault, NewMemberPayload, StreamProposal, StreamType}};
 
+/// Adds `member` with the weight and roles in the args. Authorized by the admin, or by an
+/// approved `AddMember` proposal for the same member, weight and roles when one is passed
+/// last; vaults whose admin role was renounced can only add members that way. The authority
+/// pays rent for the grown member list.
 pub fn process_add_member(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    if accounts.len() < 4 {
+    let [authority, member, multisig_info, system_program, rest @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    let [admin, member, multisig_info, system_program] = accounts else {
-        return Err(ProgramError::NotEnoughAccountKeys);
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
     };
 
-    if !admin.is_signer() {
-        return Err(ProgramError::MissingRequiredSignature);
+    if instruction_data.len() < 17 {
+        return Err(ProgramError::InvalidInstructionData);
     };
 
     let multisig_id = u64::from_le_bytes(
         instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    let (multisig_info_pda, multisig_info_bump) = pubkey::find_program_address(
-        &[b"multisig_info", admin.key().as_ref(), multisig_id.to_le_bytes().as_ref()],
-        &crate::ID
+    let weight = u64::from_le_bytes(
+        instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    if *multisig_info.key() != multisig_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+    if weight == 0 {
+        return Err(TreasuryError::InvalidMemberWeight.into());
     };
 
-    let mut multisig_account_info = MultiSignatureVault::from_account_info_mut(multisig_info)?;
+    let roles = instruction_data[16];
 
-    if multisig_account_info.admin != *admin.key() {
-        log!("Only the vault admin can add members");
-        return Err(ProgramError::InvalidAccountData);
+    if roles & !Member::ALL_ROLES != 0 {
+        return Err(TreasuryError::InvalidMemberRoles.into());
     };
 
-    if *member.key() == multisig_account_info.admin {
-        log!("Admin cannot be added as a regular member");
-        return Err(ProgramError::InvalidAccountData);
+    if *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    let multisig_account_info = MultiSignatureVault::from_account_info(multisig_info)?;
+
+    let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_account_info.creator, multisig_id);
+
+    if *multisig_info.key() != multisig_info_pda {
+        return Err(TreasuryError::InvalidMultisigAccount.into());
     };
 
-    if !multisig_account_info.is_active {
-        log!("Cannot add member: vault is inactive");
-        return Err(ProgramError::InvalidAccountData);
+    if *member.key() == multisig_account_info.admin {
+        log!("Admin cannot be added as a regular member");
+        return Err(TreasuryError::AdminCannotBeMember.into());
     };
 
-    if multisig_account_info.member_count >= 10 {
-        log!("Cannot add member: maximum capacity (10) reached");
-        return Err(ProgramError::AccountDataTooSmall);
+    if multisig_account_info.member_count >= MultiSignatureVault::MAX_MEMBERS
+        || multisig_account_info.member_slots >= MultiSignatureVault::MAX_MEMBER_SLOTS
+    {
+        log!("Cannot add member: maximum capacity ({}) reached", MultiSignatureVault::MAX_MEMBERS);
+        return Err(TreasuryError::MemberLimitReached.into());
     }
 
     if *member.key() == Pubkey::default() {
         log!("Cannot add default/zero address as member");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidMemberKey.into());
     }
 
-    for i in 0..10 {
-        if multisig_account_info.member_keys[i] == *member.key() {
-            log!("Member already exists in the member list!");
-            return Err(ProgramError::InvalidAccountData);
-        }
-    }
+    let (admin, is_active) = (multisig_account_info.admin, multisig_account_info.is_active);
+    drop(multisig_account_info);
 
-    let mut member_added = false;
-    for i in 0..10 {
-        if multisig_account_info.member_keys[i] == Pubkey::default() {
-            multisig_account_info.member_keys[i] = *member.key();
-            member_added = true;
-            log!("Member added successfully at index {}", i);
-            break;
-        }
+    if MultiSignatureVault::is_member(&MultiSignatureVault::members(multisig_info)?, member.key()) {
+        log!("Member already exists in the member list!");
+        return Err(TreasuryError::MemberAlreadyExists.into());
     }
 
-    if !member_added {
-        log!("Cannot add member: maximum capacity (10) reached");
-        return Err(ProgramError::AccountDataTooSmall);
-    }
+    let stream_proposal_account = match rest {
+        [] => {
+            if *authority.key() != admin {
+                log!("Only the vault admin can add members");
+                return Err(TreasuryError::NotAdmin.into());
+            };
+
+            // A paused vault changes membership only by proposal.
+            if !is_active {
+                log!("Cannot add member: vault is inactive");
+                return Err(TreasuryError::MultisigInactive.into());
+            };
+
+            None
+        },
+        [stream_proposal_account, ..] => {
+            verify_governed_proposal(authority, stream_proposal_account, multisig_info, StreamType::AddMember)?;
+
+            let approved = NewMemberPayload::try_from_bytes(&StreamProposal::payload(stream_proposal_account)?)
+                .map_err(|_| TreasuryError::InvalidProposalPayload)?;
+
+            if approved != (NewMemberPayload { member: *member.key(), weight, roles }) {
+                msg!("Member does not match the approved proposal");
+                return Err(TreasuryError::ProposalAccountMismatch.into());
+            };
+
+            Some(stream_proposal_account)
+        },
+    };
 
-    multisig_account_info.member_count = multisig_account_info.member_count.checked_add(1).unwrap();
+    let slot = MultiSignatureVault::push_member(multisig_info, authority, Member::new(*member.key(), weight, roles))?;
+    log!("Member added successfully at index {} with weight {} and roles {}", slot, weight, roles);
+
+    let mut multisig_account_info = MultiSignatureVault::from_account_info_mut(multisig_info)?;
     multisig_account_info.last_updated = Clock::get()?.unix_timestamp;
+
+    if let Some(stream_proposal_account) = stream_proposal_account {
+        let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+        mark_proposal_executed(&mut stream_proposal, &mut multisig_account_info, authority.key())?;
+    };
+
     log!("Member added successfully! Total members: {}", multisig_account_info.member_count);
 
     Ok(())
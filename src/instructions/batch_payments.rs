@@ -1,109 +1,97 @@
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{rent::Rent, Sysvar}, *};
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, *};
 
-use pinocchio_token::{instructions::{InitializeAccount3, Transfer}, state::TokenAccount};
+use pinocchio_token::{instructions::TransferChecked, state::{Mint, TokenAccount}};
 
+use crate::{error::TreasuryError, pda};
 use crate::states::BatchState;
 use crate::states::BatchTransfer;
 
+/// Pays `amount_per_recipient` from the creator's token account to each recipient in the
+/// instruction data. Recipient token accounts are passed after the token program, in the
+/// same order as the recipients, and must already exist.
 pub fn process_batch_transfer(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    if accounts.len() < 5 {
+    let [creator, creator_token_account, mint, batch_state, token_program, recipient_token_accounts @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
-    }
+    };
 
-    let [creator, creator_token_account, mint, batch_state, batch_transfer, token_program, rest @..] = accounts else {
-        return Err(ProgramError::NotEnoughAccountKeys);
+    if !creator.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
     };
 
-    if instruction_data.len() != BatchTransfer::LEN {
-        return Err(ProgramError::InvalidInstructionData);
-    }
+    if *token_program.key() != pinocchio_token::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
 
-    let mut batch_state_account_info = BatchState::from_account_info_mut(batch_state)?;
+    if *batch_state.owner() != crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    };
 
-    let (batch_state_pda, batch_bump) = pubkey::find_program_address(
-        &[b"batch_state", creator.key().as_ref(), mint.key().as_ref(), &batch_state_account_info.batch_id.to_le_bytes()],
-        &crate::ID
-    );
+    let batch_transfer = BatchTransfer::try_from_bytes(instruction_data)?;
 
-    let (creator_token_account_pda, bump2) = pubkey::find_program_address(
-        &[b"creator_token_account", creator.key().as_ref(), mint.key().as_ref()],
-        &crate::ID
-    );
+    let mut batch_state_account_info = BatchState::from_account_info_mut(batch_state)?;
 
-    if *creator_token_account.key() != creator_token_account_pda {
-        return Err(ProgramError::InvalidAccountData);
-    };
+    let (batch_state_pda, _) = pda::find_batch_state(creator.key(), mint.key(), batch_transfer.batch_id);
 
     if *batch_state.key() != batch_state_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidBatchAccount.into());
     };
 
-    let (batch_transfer_pda, bump) = pubkey::find_program_address(
-        &[b"batch_transfer", batch_state.key().as_ref()],
-        &crate::ID
-    );
+    let (creator_token_account_pda, _) = pda::find_creator_token_account(creator.key(), mint.key());
 
-    if *batch_transfer.key() != batch_transfer_pda {
-        return Err(ProgramError::InvalidAccountData);
+    if *creator_token_account.key() != creator_token_account_pda {
+        return Err(TreasuryError::InvalidBatchAccount.into());
     };
 
-    let batch_transfer = BatchTransfer::from_account_info(batch_transfer)?;
-
     batch_state_account_info.validate_authority(creator.key())?;
     batch_state_account_info.validate_mint(mint.key())?;
 
     if batch_state_account_info.batch_id != batch_transfer.batch_id {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidBatchAccount.into());
     };
 
-    for recipient_pubkey in batch_transfer.get_active_recipients() {
-        let recipient_token_account = rest
-            .iter()
-            .find(|acc| acc.key() == recipient_pubkey)
-            .ok_or(ProgramError::InvalidAccountData)?;
-
-        if recipient_token_account.data_len() == 0 {
-            msg!("Creating recipient token account");
-
-            let lamports = Rent::get()?.minimum_balance(TokenAccount::LEN);
-
-            CreateAccount {
-                from: creator,
-                to: recipient_token_account,
-                lamports,
-                space: TokenAccount::LEN as u64,
-                owner: &pinocchio_token::id(),
-            }
-            .invoke()?;
-
-            InitializeAccount3 {
-                account: recipient_token_account,
-                mint,
-                owner: recipient_pubkey,
-            }
-            .invoke()?;
+    let remaining = batch_state_account_info.total_recipients.saturating_sub(batch_state_account_info.processed_count);
+    if batch_state_account_info.is_completed() || batch_transfer.num_recipients > remaining {
+        msg!("Batch has fewer remaining recipients than requested");
+        return Err(TreasuryError::InvalidBatchAccount.into());
+    };
+
+    if recipient_token_accounts.len() < batch_transfer.num_recipients as usize {
+        return Err(TreasuryError::BatchRecipientMissing.into());
+    };
+
+    {
+        let creator_token_account_info = TokenAccount::from_account_info(creator_token_account)?;
+
+        if creator_token_account_info.owner() != creator.key() || creator_token_account_info.mint() != mint.key() {
+            return Err(TreasuryError::TokenAccountMismatch.into());
+        };
+
+        if creator_token_account_info.amount() < batch_transfer.total_amount_needed() {
+            return Err(ProgramError::InsufficientFunds);
         };
+    }
 
-        let batch_id = batch_state_account_info.batch_id.to_le_bytes();
+    let decimals = Mint::from_account_info(mint)?.decimals();
 
-        let seeds = seeds!(
-            b"batch_state",
-            creator.key().as_ref(),
-            mint.key().as_ref(),
-            batch_id.as_ref()
-        );
+    for (recipient, recipient_token_account) in batch_transfer.get_active_recipients().iter().zip(recipient_token_accounts) {
+        {
+            let recipient_token_account_info = TokenAccount::from_account_info(recipient_token_account)?;
 
-        let signer_seeds = Signer::from(&seeds);
+            if recipient_token_account_info.owner() != recipient || recipient_token_account_info.mint() != mint.key() {
+                msg!("Recipient token account does not match the batch recipient");
+                return Err(TreasuryError::BatchRecipientMissing.into());
+            };
+        }
 
-        Transfer {
+        TransferChecked {
             from: creator_token_account,
+            mint,
             to: recipient_token_account,
-            authority: batch_state,
+            authority: creator,
             amount: batch_transfer.amount_per_recipient,
-        }
-        .invoke_signed(&[signer_seeds])?; 
+            decimals,
+        }.invoke()?;
 
         batch_state_account_info.increment_processed();
     };
@@ -121,7 +109,7 @@ mod testing {
         pubkey::Pubkey,
         pubkey,
     };
-    use crate::states::{BatchState, BatchTransfer};
+    use crate::states::{AccountHeader, BatchState};
 
     const PROGRAM_ID: Pubkey = pubkey!("7B3prxsmARuNjdD5qa5CmDur1tPWbH4UNbZu1AVJCRJo");
     const CREATOR: Pubkey = Pubkey::new_from_array([1u8; 32]);
@@ -145,28 +133,11 @@ mod testing {
             &PROGRAM_ID
         );
 
-        let (batch_transfer_pda, transfer_bump) = Pubkey::find_program_address(
-            &[b"batch_transfer", batch_state_pda.as_ref()],
-            &PROGRAM_ID
-        );
 
         let (system_program_id, system_account) = program::keyed_account_for_system_program();
         let (token_program_id, token_program_account) = program::keyed_account_for_system_program();
 
-        // Create instruction data - BatchTransfer serialized data
-        let mut batch_transfer_data = vec![0u8; BatchTransfer::LEN];
-        
-        // Create a mock BatchTransfer struct data
-        // Assuming BatchTransfer has: batch_id (8 bytes) + amount_per_recipient (8 bytes) + recipients array
-        batch_transfer_data[0..8].copy_from_slice(&BATCH_ID.to_le_bytes()); // batch_id
-        batch_transfer_data[8..16].copy_from_slice(&1000u64.to_le_bytes()); // amount_per_recipient
-        // Add recipient pubkeys to the data (assuming they come after amount)
-        batch_transfer_data[16..48].copy_from_slice(RECIPIENT_1.as_ref());
-        batch_transfer_data[48..80].copy_from_slice(RECIPIENT_2.as_ref());
-
-        let mut instruction_data = vec![0u8; 1 + BatchTransfer::LEN];
-        instruction_data[0] = 1; // ProcessBatchTransfer discriminator
-        instruction_data[1..].copy_from_slice(&batch_transfer_data);
+        let instruction_data = batch_transfer_instruction_data(1000, &[RECIPIENT_1, RECIPIENT_2]);
 
         let instruction = Instruction {
             program_id: PROGRAM_ID,
@@ -175,7 +146,6 @@ mod testing {
                 AccountMeta::new(creator_token_account_pda, false),
                 AccountMeta::new_readonly(MINT, false),
                 AccountMeta::new(batch_state_pda, false),
-                AccountMeta::new_readonly(batch_transfer_pda, false),
                 AccountMeta::new_readonly(token_program_id, false),
                 // Recipient token accounts
                 AccountMeta::new(RECIPIENT_1, false),
@@ -221,13 +191,15 @@ mod testing {
 
         // Batch state account (initialized)
         let mut batch_state_data = vec![0u8; BatchState::LEN];
-        batch_state_data[0..8].copy_from_slice(&BATCH_ID.to_le_bytes()); // batch_id
-        batch_state_data[8..40].copy_from_slice(CREATOR.as_ref()); // authority
-        batch_state_data[40..72].copy_from_slice(MINT.as_ref()); // mint
-        batch_state_data[72] = 2; // total_recipients
-        batch_state_data[73] = 0; // processed_count
-        batch_state_data[74] = 0; // is_completed
-        batch_state_data[75] = batch_bump; // bump
+        batch_state_data[0..8].copy_from_slice(&BatchState::DISCRIMINATOR); // discriminator
+        batch_state_data[8] = BatchState::VERSION; // version
+        batch_state_data[16..24].copy_from_slice(&BATCH_ID.to_le_bytes()); // batch_id
+        batch_state_data[24..56].copy_from_slice(CREATOR.as_ref()); // authority
+        batch_state_data[56..88].copy_from_slice(MINT.as_ref()); // mint
+        batch_state_data[88] = 2; // total_recipients
+        batch_state_data[89] = 0; // processed_count
+        batch_state_data[90] = 0; // is_completed
+        batch_state_data[91] = batch_bump; // bump
         
         let batch_state_account = Account {
             lamports: 2_000_000,
@@ -237,22 +209,8 @@ mod testing {
             rent_epoch: 0,
         };
 
-        // Batch transfer account (initialized)
-        let mut batch_transfer_data = vec![0u8; BatchTransfer::LEN];
-        batch_transfer_data[0..8].copy_from_slice(&BATCH_ID.to_le_bytes()); // batch_id
-        batch_transfer_data[8..16].copy_from_slice(&1000u64.to_le_bytes()); // amount_per_recipient
-        batch_transfer_data[16..48].copy_from_slice(RECIPIENT_1.as_ref()); // recipient 1
-        batch_transfer_data[48..80].copy_from_slice(RECIPIENT_2.as_ref()); // recipient 2
-        
-        let batch_transfer_account = Account {
-            lamports: 2_000_000,
-            data: batch_transfer_data,
-            owner: PROGRAM_ID,
-            executable: false,
-            rent_epoch: 0,
-        };
 
-        // Recipient token accounts (uninitialized - will be created)
+        // Recipient token accounts (uninitialized - must be created before the batch runs)
         let recipient_1_token_account = Account {
             lamports: 0,
             data: vec![],
@@ -276,13 +234,12 @@ mod testing {
                 (creator_token_account_pda, creator_token_account),
                 (MINT, mint_account),
                 (batch_state_pda, batch_state_account),
-                (batch_transfer_pda, batch_transfer_account),
                 (token_program_id, token_program_account),
                 (RECIPIENT_1, recipient_1_token_account),
                 (RECIPIENT_2, recipient_2_token_account),
                 (system_program_id, system_account),
             ],
-            &[Check::success()],
+            &[Check::err(solana_sdk::program_error::ProgramError::InvalidAccountData)],
         );
     }
 
@@ -300,24 +257,11 @@ mod testing {
             &PROGRAM_ID
         );
 
-        let (batch_transfer_pda, transfer_bump) = Pubkey::find_program_address(
-            &[b"batch_transfer", batch_state_pda.as_ref()],
-            &PROGRAM_ID
-        );
 
         let (system_program_id, system_account) = program::keyed_account_for_system_program();
         let (token_program_id, token_program_account) = program::keyed_account_for_system_program();
 
-        // Create instruction data
-        let mut batch_transfer_data = vec![0u8; BatchTransfer::LEN];
-        batch_transfer_data[0..8].copy_from_slice(&BATCH_ID.to_le_bytes());
-        batch_transfer_data[8..16].copy_from_slice(&500u64.to_le_bytes()); // smaller amount
-        batch_transfer_data[16..48].copy_from_slice(RECIPIENT_1.as_ref());
-        batch_transfer_data[48..80].copy_from_slice(RECIPIENT_2.as_ref());
-
-        let mut instruction_data = vec![0u8; 1 + BatchTransfer::LEN];
-        instruction_data[0] = 1; // ProcessBatchTransfer discriminator
-        instruction_data[1..].copy_from_slice(&batch_transfer_data);
+        let instruction_data = batch_transfer_instruction_data(500, &[RECIPIENT_1, RECIPIENT_2]);
 
         let instruction = Instruction {
             program_id: PROGRAM_ID,
@@ -326,7 +270,6 @@ mod testing {
                 AccountMeta::new(creator_token_account_pda, false),
                 AccountMeta::new_readonly(MINT, false),
                 AccountMeta::new(batch_state_pda, false),
-                AccountMeta::new_readonly(batch_transfer_pda, false),
                 AccountMeta::new_readonly(token_program_id, false),
                 AccountMeta::new(RECIPIENT_1, false),
                 AccountMeta::new(RECIPIENT_2, false),
@@ -368,13 +311,15 @@ mod testing {
         };
 
         let mut batch_state_data = vec![0u8; BatchState::LEN];
-        batch_state_data[0..8].copy_from_slice(&BATCH_ID.to_le_bytes());
-        batch_state_data[8..40].copy_from_slice(CREATOR.as_ref());
-        batch_state_data[40..72].copy_from_slice(MINT.as_ref());
-        batch_state_data[72] = 2;
-        batch_state_data[73] = 0;
-        batch_state_data[74] = 0;
-        batch_state_data[75] = batch_bump;
+        batch_state_data[0..8].copy_from_slice(&BatchState::DISCRIMINATOR); // discriminator
+        batch_state_data[8] = BatchState::VERSION; // version
+        batch_state_data[16..24].copy_from_slice(&BATCH_ID.to_le_bytes()); // batch_id
+        batch_state_data[24..56].copy_from_slice(CREATOR.as_ref()); // authority
+        batch_state_data[56..88].copy_from_slice(MINT.as_ref()); // mint
+        batch_state_data[88] = 2; // total_recipients
+        batch_state_data[89] = 0; // processed_count
+        batch_state_data[90] = 0; // is_completed
+        batch_state_data[91] = batch_bump; // bump
         
         let batch_state_account = Account {
             lamports: 2_000_000,
@@ -384,19 +329,6 @@ mod testing {
             rent_epoch: 0,
         };
 
-        let mut batch_transfer_data = vec![0u8; BatchTransfer::LEN];
-        batch_transfer_data[0..8].copy_from_slice(&BATCH_ID.to_le_bytes());
-        batch_transfer_data[8..16].copy_from_slice(&500u64.to_le_bytes());
-        batch_transfer_data[16..48].copy_from_slice(RECIPIENT_1.as_ref());
-        batch_transfer_data[48..80].copy_from_slice(RECIPIENT_2.as_ref());
-        
-        let batch_transfer_account = Account {
-            lamports: 2_000_000,
-            data: batch_transfer_data,
-            owner: PROGRAM_ID,
-            executable: false,
-            rent_epoch: 0,
-        };
 
         // Pre-initialized recipient token accounts
         let mut recipient_1_token_data = vec![0u8; TokenAccount::LEN];
@@ -434,7 +366,6 @@ mod testing {
                 (creator_token_account_pda, creator_token_account),
                 (MINT, mint_account),
                 (batch_state_pda, batch_state_account),
-                (batch_transfer_pda, batch_transfer_account),
                 (token_program_id, token_program_account),
                 (RECIPIENT_1, recipient_1_token_account),
                 (RECIPIENT_2, recipient_2_token_account),
@@ -448,8 +379,7 @@ mod testing {
     fn test_process_batch_transfer_insufficient_accounts() {
         let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/batch_token_transfer");
 
-        let mut instruction_data = vec![0u8; 1 + BatchTransfer::LEN];
-        instruction_data[0] = 1; // ProcessBatchTransfer discriminator
+        let instruction_data = batch_transfer_instruction_data(1000, &[RECIPIENT_1, RECIPIENT_2]);
 
         let instruction = Instruction {
             program_id: PROGRAM_ID,
@@ -501,15 +431,11 @@ mod testing {
             &PROGRAM_ID
         );
 
-        let (batch_transfer_pda, _) = Pubkey::find_program_address(
-            &[b"batch_transfer", batch_state_pda.as_ref()],
-            &PROGRAM_ID
-        );
 
         let (token_program_id, token_program_account) = program::keyed_account_for_system_program();
 
         // Invalid instruction data (too short)
-        let instruction_data = vec![1u8]; // Only discriminator, missing BatchTransfer data
+        let instruction_data = vec![14u8]; // Only discriminator, missing BatchTransfer data
 
         let instruction = Instruction {
             program_id: PROGRAM_ID,
@@ -518,7 +444,6 @@ mod testing {
                 AccountMeta::new(creator_token_account_pda, false),
                 AccountMeta::new_readonly(MINT, false),
                 AccountMeta::new(batch_state_pda, false),
-                AccountMeta::new_readonly(batch_transfer_pda, false),
                 AccountMeta::new_readonly(token_program_id, false),
             ],
             data: instruction_data
@@ -531,10 +456,20 @@ mod testing {
                 (creator_token_account_pda, Account::default()),
                 (MINT, Account::default()),
                 (batch_state_pda, Account::default()),
-                (batch_transfer_pda, Account::default()),
                 (token_program_id, token_program_account),
             ],
             &[Check::err(solana_sdk::program_error::ProgramError::InvalidAccountData)],
         );
     }
-}
\ No newline at end of file
+
+    fn batch_transfer_instruction_data(amount_per_recipient: u64, recipients: &[Pubkey]) -> Vec<u8> {
+        let mut instruction_data = vec![14u8]; // BatchTransfer discriminator
+        instruction_data.extend_from_slice(&BATCH_ID.to_le_bytes());
+        instruction_data.extend_from_slice(&amount_per_recipient.to_le_bytes());
+        instruction_data.push(recipients.len() as u8);
+        for recipient in recipients {
+            instruction_data.extend_from_slice(recipient.as_ref());
+        }
+        instruction_data
+    }
+}
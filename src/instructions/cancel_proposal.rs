@@ -0,0 +1,94 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, Sysvar}, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, instructions::close_proposal::{close_proposal, verify_proposal_refund}, states::{Member, MultiSignatureVault, ProposalStatus, StreamProposal}};
+
+/// Cancels a proposal that has not been executed, rejected or cancelled, and closes its
+/// account, refunding the rent to the proposer. The admin may always cancel. The proposer
+/// may cancel alone before any votes are cast, and afterwards with the consent of members
+/// holding a majority of the voting weight, who sign as the accounts after `proposer`.
+pub fn process_cancel_proposal(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [authority, stream_proposal_account, multisig_account, proposer, consenting_members @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    verify_proposal_refund(stream_proposal_account, multisig_account, proposer, false)?;
+
+    let multisig_info = MultiSignatureVault::from_account_info(multisig_account)?;
+    let stream_proposal = StreamProposal::from_account_info(stream_proposal_account)?;
+
+    let status = ProposalStatus::try_from(&stream_proposal.status)?;
+    if status != ProposalStatus::Active && status != ProposalStatus::Approved {
+        return Err(TreasuryError::ProposalFinalized.into());
+    };
+
+    let proposal_id = stream_proposal.proposal_id;
+    let has_votes = stream_proposal.total_vote_count > 0;
+    let is_admin = *authority.key() == multisig_info.admin;
+    let total_weight = multisig_info.total_weight;
+    drop(stream_proposal);
+    drop(multisig_info);
+
+    if !is_admin {
+        if *authority.key() != *proposer.key() {
+            return Err(TreasuryError::CancellationNotAllowed.into());
+        };
+
+        if has_votes {
+            let members = MultiSignatureVault::members(multisig_account)?;
+            let consent = consenting_weight(&members, authority, consenting_members)?;
+
+            if consent.checked_mul(2).ok_or(ProgramError::ArithmeticOverflow)? <= total_weight {
+                log!("Cancellation needs a majority of the voting weight, got {} of {}", consent, total_weight);
+                return Err(TreasuryError::CancellationNotAllowed.into());
+            };
+        };
+    };
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    {
+        let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+        multisig_info.active_proposals = multisig_info.active_proposals.saturating_sub(1);
+        multisig_info.last_updated = current_time;
+
+        let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+        stream_proposal.status = ProposalStatus::Cancelled as u8;
+    }
+
+    close_proposal(stream_proposal_account, proposer, current_time)?;
+
+    log!("Proposal {} cancelled", proposal_id);
+
+    Ok(())
+}
+
+/// Voting weight of the distinct current members among `proposer` and `signers`, all of
+/// whom must sign.
+pub fn consenting_weight(members: &[Member], proposer: &AccountInfo, signers: &[AccountInfo]) -> Result<u64, ProgramError> {
+    let mut counted: Vec<&Pubkey> = Vec::new();
+    let mut weight = 0u64;
+
+    for signer in core::iter::once(proposer).chain(signers) {
+        if !signer.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        };
+
+        if counted.contains(&signer.key()) {
+            continue;
+        };
+
+        if let Some(slot) = MultiSignatureVault::member_slot(members, signer.key()) {
+            weight = weight.checked_add(members[slot].voting_weight()).ok_or(ProgramError::ArithmeticOverflow)?;
+        };
+
+        counted.push(signer.key());
+    }
+
+    Ok(weight)
+}
@@ -2,7 +2,7 @@ use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::P
 use pinocchio_token::{instructions::{SetAuthority, Transfer}, state::TokenAccount};
 use pinocchio_token::instructions::AuthorityType;
 
-use crate::{instructions::withdraw_stream_payments::calculate_withdrawable_amount, states::PaymentStreamingInfo};
+use crate::{error::TreasuryError, pda, instructions::{execute_proposal::{mark_proposal_executed, verify_governed_proposal}, withdraw_stream_payments::calculate_withdrawable_amount}, states::{MultiSignatureVault, PaymentStreamingInfo, ProposalAction, StreamProposal, StreamType}};
 
 pub fn process_cancel_stream(accounts: &[AccountInfo]) -> ProgramResult {
 
@@ -18,24 +18,21 @@ pub fn process_cancel_stream(accounts: &[AccountInfo]) -> ProgramResult {
 
     if !stream_account_info_mut.is_active {
         msg!("Payment stream is not active");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::StreamInactive.into());
     }
 
     if *sender.key() != stream_account_info_mut.sender {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::UnauthorizedStreamParty.into());
     }
 
     if *recipient.key() != stream_account_info_mut.recipient {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::UnauthorizedStreamParty.into());
     }
 
-    let (payment_stream_info_pda, payment_stream_info_bump) = pubkey::find_program_address(
-        &[b"payment_stream_info", sender.key().as_ref(), recipient.key().as_ref()],
-        &crate::ID
-    );
+    let (payment_stream_info_pda, payment_stream_info_bump) = pda::find_payment_stream(sender.key(), recipient.key());
 
     if *payment_stream_info_account.key() != payment_stream_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidStreamAccount.into());
     };
 
     let sender_token_account_info = TokenAccount::from_account_info(sender_token_account)?;
@@ -54,7 +51,7 @@ pub fn process_cancel_stream(accounts: &[AccountInfo]) -> ProgramResult {
 
     let bump_ref = &[payment_stream_info_bump];
     let seeds = seeds!(
-        b"payment_stream_info", 
+        pda::PAYMENT_STREAM_SEED,
         sender.key().as_ref(), 
         recipient.key().as_ref(),
         bump_ref
@@ -82,4 +79,138 @@ pub fn process_cancel_stream(accounts: &[AccountInfo]) -> ProgramResult {
     }.invoke_signed(&[signer_seeds_clone])?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Executes an approved `CancelStream` proposal for a treasury-funded stream. The recipient
+/// is paid what has streamed so far and the rest of the escrow goes back to the treasury;
+/// both transfers are signed by the stream PDA that owns the escrow. Fails while the vault
+/// is paused.
+pub fn process_cancel_treasury_stream(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account, treasury_vault, stream_escrow, recipient_token_account, payment_stream_info_account, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if *token_program.key() != pinocchio_token::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::CancelStream)?;
+
+    // Cancelling pays the recipient what has accrued, which a paused vault must not release.
+    MultiSignatureVault::check_not_paused(multisig_account)?;
+
+    let ProposalAction::CancelStream(recipient) = ProposalAction::try_from_payload(&StreamType::CancelStream, &StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)? else {
+        return Err(TreasuryError::InvalidProposalPayload.into());
+    };
+
+    let (payment_stream_info_pda, payment_stream_info_bump) = pda::find_payment_stream(multisig_account.key(), &recipient);
+
+    if *payment_stream_info_account.key() != payment_stream_info_pda {
+        return Err(TreasuryError::InvalidStreamAccount.into());
+    };
+
+    if *treasury_vault.key() != MultiSignatureVault::from_account_info(multisig_account)?.treasury_vault {
+        msg!("Treasury vault does not belong to this multisig");
+        return Err(TreasuryError::InvalidTreasuryVault.into());
+    };
+
+    let mut stream_account_info_mut = PaymentStreamingInfo::from_account_info_mut(payment_stream_info_account)?;
+
+    if !stream_account_info_mut.is_active {
+        msg!("Payment stream is not active");
+        return Err(TreasuryError::StreamInactive.into());
+    };
+
+    if *stream_escrow.key() != stream_account_info_mut.sender_token_account {
+        return Err(TreasuryError::InvalidStreamAccount.into());
+    };
+
+    if *recipient_token_account.key() != stream_account_info_mut.recipient_token_account {
+        return Err(TreasuryError::TokenAccountMismatch.into());
+    };
+
+    let escrow_balance = {
+        let stream_escrow_info = TokenAccount::from_account_info(stream_escrow)?;
+
+        if stream_escrow_info.owner() != payment_stream_info_account.key() {
+            return Err(TreasuryError::InvalidStreamAccount.into());
+        };
+
+        if stream_escrow_info.mint() != TokenAccount::from_account_info(treasury_vault)?.mint() {
+            return Err(TreasuryError::InvalidTreasuryVault.into());
+        };
+
+        stream_escrow_info.amount()
+    };
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let (withdrawable_for_recipient, refund) = split_cancelled_stream(&stream_account_info_mut, escrow_balance, current_time)?;
+
+    let bump_ref = &[payment_stream_info_bump];
+    let seeds = seeds!(
+        pda::PAYMENT_STREAM_SEED,
+        multisig_account.key().as_ref(),
+        recipient.as_ref(),
+        bump_ref
+    );
+
+    if withdrawable_for_recipient > 0 {
+        Transfer {
+            from: stream_escrow,
+            to: recipient_token_account,
+            authority: payment_stream_info_account,
+            amount: withdrawable_for_recipient,
+        }.invoke_signed(&[Signer::from(&seeds)])?;
+    }
+
+    if refund > 0 {
+        Transfer {
+            from: stream_escrow,
+            to: treasury_vault,
+            authority: payment_stream_info_account,
+            amount: refund,
+        }.invoke_signed(&[Signer::from(&seeds)])?;
+    }
+
+    stream_account_info_mut.is_active = false;
+    stream_account_info_mut.withdrawn_amount += withdrawable_for_recipient;
+    drop(stream_account_info_mut);
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    msg!("Treasury-funded payment stream cancelled!");
+
+    Ok(())
+}
+
+/// Splits an escrow holding `escrow_balance` between the recipient, who gets what has
+/// streamed by `current_time`, and the treasury, which is refunded the rest.
+fn split_cancelled_stream(stream_info: &PaymentStreamingInfo, escrow_balance: u64, current_time: i64) -> Result<(u64, u64), ProgramError> {
+    let withdrawable = calculate_withdrawable_amount(stream_info, current_time)?.min(escrow_balance);
+
+    Ok((withdrawable, escrow_balance - withdrawable))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_cancelled_stream_refunds_unstreamed_tokens() {
+        let mut stream_info: PaymentStreamingInfo = unsafe { core::mem::zeroed() };
+        stream_info.total_amount = 1_000;
+        stream_info.start_time = 0;
+        stream_info.end_time = 100;
+        stream_info.withdrawn_amount = 200;
+        stream_info.is_active = true;
+
+        assert_eq!(split_cancelled_stream(&stream_info, 800, -10), Ok((0, 800)));
+        assert_eq!(split_cancelled_stream(&stream_info, 800, 50), Ok((300, 500)));
+        assert_eq!(split_cancelled_stream(&stream_info, 800, 150), Ok((800, 0)));
+    }
+}
@@ -0,0 +1,86 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, instructions::vote_on_proposal::open_ballot, states::{Member, MultiSignatureVault, StreamProposal}};
+
+/// Withdraws the voter's vote from a proposal that is still open.
+pub fn process_revoke_vote(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    revise_vote(accounts, instruction_data, None)
+}
+
+/// Replaces the voter's vote on a proposal that is still open with the opposite one.
+pub fn process_change_vote(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    if instruction_data.len() < 24 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let vote_type = u64::from_le_bytes(
+        instruction_data[16..24].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    if vote_type > 1 {
+        return Err(TreasuryError::InvalidVoteType.into());
+    }
+
+    revise_vote(accounts, instruction_data, Some(vote_type == 0))
+}
+
+/// Strikes the voter's existing vote and, if `approve` is given, records the new one. The
+/// proposal is re-tallied with the current weights and threshold, so an `Approved` proposal
+/// that no longer meets its threshold goes back to `Active`, and one that meets it again is
+/// timelocked afresh.
+fn revise_vote(accounts: &[AccountInfo], instruction_data: &[u8], approve: Option<bool>) -> ProgramResult {
+
+    let [voter, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !voter.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if instruction_data.len() < 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let proposal_id = u64::from_le_bytes(
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let multisig_id = u64::from_le_bytes(
+        instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+    );
+
+    let ballot = open_ballot(stream_proposal_account, multisig_account, proposal_id, multisig_id, true)?;
+
+    let members = MultiSignatureVault::members(multisig_account)?;
+
+    let voter_slot = MultiSignatureVault::member_slot(&members, voter.key())
+        .ok_or(TreasuryError::NotMultisigMember)?;
+
+    if approve.is_some() && !members[voter_slot].has_role(Member::VOTER) {
+        return Err(TreasuryError::MissingMemberRole.into());
+    }
+
+    StreamProposal::with_votes_mut(stream_proposal_account, |proposal, votes| {
+        if approve.is_some_and(|approve| approve == proposal.approved_by(votes, voter_slot)) {
+            return Err(TreasuryError::AlreadyVoted.into());
+        }
+
+        proposal.required_threshold = ballot.required_threshold;
+
+        if !proposal.remove_vote(votes, &members, voter_slot) {
+            return Err(TreasuryError::VoteNotFound.into());
+        }
+
+        if let Some(approve) = approve {
+            proposal.record_vote(votes, &members, voter_slot, approve)?;
+        }
+
+        proposal.update_status(ballot.current_time, ballot.timelock_delay)?;
+
+        log!("Vote revised on proposal {}: approvals {}, rejections {}", proposal.proposal_id, proposal.approval_weight, proposal.rejection_weight);
+
+        Ok(())
+    })
+}
@@ -1,7 +1,7 @@
 use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, *};
 use pinocchio_token::{instructions::TransferChecked, state::Mint};
 
-use crate::states::{VestingContractInfo, VestingRecordInfo};
+use crate::{error::TreasuryError, pda, states::{MultiSignatureVault, VestingContractInfo, VestingRecordInfo}};
 
 pub fn process_claim_vested_tokens(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
@@ -10,46 +10,42 @@ pub fn process_claim_vested_tokens(accounts: &[AccountInfo], instruction_data: &
     };
 
     if !beneficiary.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    if instruction_data.len() < 32 {
+        return Err(ProgramError::InvalidInstructionData);
     };
 
     let mut company_name = [0u8; 32];
     company_name.copy_from_slice(&instruction_data[0..32]);
 
-    let (vesting_contract_info_pda, vesting_contract_bump) = pubkey::find_program_address(
-        &[company_name.as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
+    let (vesting_contract_info_pda, vesting_contract_bump) = pda::find_vesting_contract(&company_name, creator.key());
 
     if *vesting_contract_info.key() != vesting_contract_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let (vesting_record_info_pda, vesting_record_info_bump) = pubkey::find_program_address(
-        &[b"vesting_record_info", vesting_contract_info.key().as_ref(), beneficiary.key().as_ref()],
-        &crate::ID
-    );
+    let (vesting_record_info_pda, vesting_record_info_bump) = pda::find_vesting_record(vesting_contract_info.key(), beneficiary.key());
 
     if *vesting_record_info.key() != vesting_record_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let (vault_pda, vault_bump) = pubkey::find_program_address(
-        &[b"vault", mint.key().as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
-
-    if *vault.key() != vault_pda {
-        return Err(ProgramError::InvalidAccountData);
+    // Contracts created before vaults were seeded per contract keep their original vault.
+    if *vault.key() != VestingContractInfo::from_account_info(vesting_contract_info)?.vault_account {
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let (beneficiary_token_account_pda, beneficiary_token_account_pda_bump) = pubkey::find_program_address(
-        &[beneficiary.key().as_ref(), mint.key().as_ref()],
-        &crate::ID
-    );
+    let (beneficiary_token_account_pda, beneficiary_token_account_pda_bump) = pda::find_beneficiary_token_account(beneficiary.key(), mint.key());
 
     if *beneficiary_token_account.key() != beneficiary_token_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
+    };
+
+    // Treasury-funded vesting is created by the multisig account itself.
+    if *creator.owner() == crate::ID {
+        MultiSignatureVault::check_not_paused(creator)?;
     };
 
     let mut vesting_contract_info_mut = VestingContractInfo::from_account_info_mut(vesting_contract_info)?;
@@ -57,39 +53,39 @@ pub fn process_claim_vested_tokens(accounts: &[AccountInfo], instruction_data: &
     let current_time = Clock::get()?.unix_timestamp;
 
     if current_time < vesting_record_info_mut.start_time {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::VestingNotStarted.into());
     };
 
     if vesting_contract_info_mut.is_active == false {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::VestingInactive.into());
     };
 
     let total_vested_tokens = vesting_record_info_mut.total_vested_tokens;
     let total_claimed_tokens = vesting_record_info_mut.total_claimed_tokens_by_beneficiary;
 
-    let unlock_time = vesting_record_info_mut.start_time.checked_add(vesting_record_info_mut.cliff_period).ok_or(ProgramError::InvalidAccountData)?;
+    let unlock_time = vesting_record_info_mut.start_time.checked_add(vesting_record_info_mut.cliff_period).ok_or(ProgramError::ArithmeticOverflow)?;
 
     if current_time < unlock_time {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::CliffNotReached.into());
     };
 
     let claimable_tokens = if current_time >= vesting_record_info_mut.end_time {
-        total_vested_tokens.checked_sub(total_claimed_tokens).ok_or(ProgramError::InvalidAccountData)?
+        total_vested_tokens.checked_sub(total_claimed_tokens).ok_or(ProgramError::ArithmeticOverflow)?
     } else {
-        let total_vesting_duration = vesting_record_info_mut.end_time.checked_sub(vesting_record_info_mut.start_time).ok_or(ProgramError::InvalidAccountData)?;
-        let elapsed_time = current_time.checked_sub(vesting_record_info_mut.start_time).ok_or(ProgramError::InvalidAccountData)?;
+        let total_vesting_duration = vesting_record_info_mut.end_time.checked_sub(vesting_record_info_mut.start_time).ok_or(ProgramError::ArithmeticOverflow)?;
+        let elapsed_time = current_time.checked_sub(vesting_record_info_mut.start_time).ok_or(ProgramError::ArithmeticOverflow)?;
         
         let total_unlocked = (total_vested_tokens as u128)
             .checked_mul(elapsed_time as u128)
-            .ok_or(ProgramError::InvalidAccountData)?
+            .ok_or(ProgramError::ArithmeticOverflow)?
             .checked_div(total_vesting_duration as u128)
-            .ok_or(ProgramError::InvalidAccountData)? as u64;
+            .ok_or(ProgramError::ArithmeticOverflow)? as u64;
             
-        total_unlocked.checked_sub(total_claimed_tokens).ok_or(ProgramError::InvalidAccountData)?
+        total_unlocked.checked_sub(total_claimed_tokens).ok_or(ProgramError::ArithmeticOverflow)?
     };
 
     if claimable_tokens == 0 {
-        return Err(ProgramError::InvalidAccountData); 
+        return Err(TreasuryError::NothingToWithdraw.into());
     };
 
     let company_name = vesting_contract_info_mut.company_name;
@@ -116,7 +112,7 @@ pub fn process_claim_vested_tokens(accounts: &[AccountInfo], instruction_data: &
 
     vesting_record_info_mut.total_claimed_tokens_by_beneficiary = total_vested_tokens; 
     vesting_record_info_mut.has_claimed = true; 
-    vesting_contract_info_mut.total_claimed_tokens = vesting_contract_info_mut.total_claimed_tokens.checked_add(claimable_tokens).ok_or(ProgramError::InvalidAccountData)?;
+    vesting_contract_info_mut.total_claimed_tokens = vesting_contract_info_mut.total_claimed_tokens.checked_add(claimable_tokens).ok_or(ProgramError::ArithmeticOverflow)?;
 
     if vesting_contract_info_mut.total_claimed_tokens >= vesting_contract_info_mut.total_vested_tokens {
         vesting_contract_info_mut.fully_claimed = true;
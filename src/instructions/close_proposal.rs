@@ -0,0 +1,83 @@
+use pinocchio::{account_info::AccountInfo, log::sol_log_data, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, pda, states::{close_account, MultiSignatureVault, ProposalRecord, ProposalStatus, StreamProposal}};
+
+/// Closes a proposal that was executed, rejected, expired or cancelled, refunding the rent
+/// to the proposer. Anyone may call it.
+pub fn process_close_proposal(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [stream_proposal_account, multisig_account, proposer] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_proposal_refund(stream_proposal_account, multisig_account, proposer, true)?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let status = ProposalStatus::try_from(&StreamProposal::from_account_info(stream_proposal_account)?.status)?;
+
+    match status {
+        ProposalStatus::Executed | ProposalStatus::Expired | ProposalStatus::Cancelled => {},
+        // Rejection happens while voting and leaves the proposal counted as active until now.
+        ProposalStatus::Rejected => {
+            let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+            multisig_info.active_proposals = multisig_info.active_proposals.saturating_sub(1);
+            multisig_info.last_updated = current_time;
+        },
+        _ => return Err(TreasuryError::ProposalStillOpen.into()),
+    };
+
+    close_proposal(stream_proposal_account, proposer, current_time)
+}
+
+/// Checks that `stream_proposal_account` is a proposal of `multisig_account` and that
+/// `proposer` created it, so closing the proposal refunds the right account. With
+/// `allow_legacy_address`, proposals at their pre-sequential-ID address are accepted too.
+pub fn verify_proposal_refund(
+    stream_proposal_account: &AccountInfo,
+    multisig_account: &AccountInfo,
+    proposer: &AccountInfo,
+    allow_legacy_address: bool,
+) -> ProgramResult {
+    let multisig_info = MultiSignatureVault::from_account_info(multisig_account)?;
+
+    let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_info.creator, multisig_info.id);
+
+    if *multisig_account.key() != multisig_info_pda {
+        return Err(TreasuryError::InvalidMultisigAccount.into());
+    };
+
+    let stream_proposal = StreamProposal::from_account_info(stream_proposal_account)?;
+
+    let (stream_proposal_pda, _) = pda::find_stream_proposal(multisig_account.key(), stream_proposal.proposal_id);
+
+    let is_legacy = || {
+        allow_legacy_address
+            && *stream_proposal_account.key() == pda::find_legacy_stream_proposal(stream_proposal.proposal_id, stream_proposal.multisig_id).0
+    };
+
+    if *stream_proposal_account.key() != stream_proposal_pda && !is_legacy() {
+        return Err(TreasuryError::InvalidProposalAccount.into());
+    };
+
+    if stream_proposal.multisig_id != multisig_info.id {
+        return Err(TreasuryError::MultisigIdMismatch.into());
+    };
+
+    if *proposer.key() != stream_proposal.proposer {
+        return Err(TreasuryError::ProposerMismatch.into());
+    };
+
+    Ok(())
+}
+
+/// Emits the proposal's `ProposalRecord` as program data, then closes its account into `proposer`.
+pub fn close_proposal(stream_proposal_account: &AccountInfo, proposer: &AccountInfo, closed_at: i64) -> ProgramResult {
+    let record = ProposalRecord::new(&*StreamProposal::from_account_info(stream_proposal_account)?, closed_at);
+
+    sol_log_data(&[&record.to_bytes()]);
+    log!("Proposal {} closed with status {}", record.proposal_id, record.status);
+
+    close_account(stream_proposal_account, proposer)
+}
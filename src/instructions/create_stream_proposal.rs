@@ -1,12 +1,12 @@
 use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
 use pinocchio_system::instructions::CreateAccount;
 
-use crate::states::{MultiSignatureVault, StreamProposal, StreamType, ProposalStatus};
+use crate::{error::TreasuryError, pda, states::{Member, MultiSignatureVault, ProposalAction, StreamProposal, StreamType, ProposalStatus}};
 
 pub fn process_create_stream_proposal(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
     if accounts.len() < 4 {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     let [proposer, stream_proposal_account, multisig_account, system_program] = accounts else {
@@ -17,95 +17,112 @@ pub fn process_create_stream_proposal(accounts: &[AccountInfo], instruction_data
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    if instruction_data.len() < 186 {
+    if instruction_data.len() < 178 {
         return Err(ProgramError::InvalidInstructionData);
     };
 
-    let proposal_id = u64::from_le_bytes(
-        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
-    );
-
     let multisig_id = u64::from_le_bytes(
-        instruction_data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+        instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    let stream_type_raw = instruction_data[16];
-
-    let required_threshold = instruction_data[17];
+    let stream_type_raw = instruction_data[8];
 
     let voting_deadline = i64::from_le_bytes(
-        instruction_data[18..26].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
+        instruction_data[9..17].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
     let mut stream_name = [0u8; 32];
-    stream_name.copy_from_slice(&instruction_data[26..58]);
+    stream_name.copy_from_slice(&instruction_data[17..49]);
 
     let mut stream_description = [0u8; 128];
-    stream_description.copy_from_slice(&instruction_data[58..186]);
+    stream_description.copy_from_slice(&instruction_data[49..177]);
+
+    let stream_type = StreamType::try_from(&stream_type_raw)?;
 
-    let _stream_type = StreamType::try_from(&stream_type_raw)?;
+    let allow_below_minimum = match instruction_data[177] {
+        0 => false,
+        1 => true,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    let payload = &instruction_data[178..];
+
+    if payload.len() > StreamProposal::MAX_PAYLOAD_LEN {
+        return Err(TreasuryError::InvalidProposalPayload.into());
+    }
 
     let current_time = Clock::get()?.unix_timestamp;
     if voting_deadline <= current_time {
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidVotingDeadline.into());
     }
 
     if stream_name[0] == 0 && stream_name[1] == 0 && stream_name[2] == 0 && stream_name[3] == 0 {
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidProposalMetadata.into());
     }
 
     if stream_description[0] == 0 {
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidProposalMetadata.into());
     }
 
     // Load and validate multisig account
-    let mut multisig_account_info_mut = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let multisig_account_info = MultiSignatureVault::from_account_info(multisig_account)?;
     
-    let (multisig_info_pda, _) = pubkey::find_program_address(
-        &[b"multisig_info", multisig_account_info_mut.admin.as_ref(), multisig_id.to_le_bytes().as_ref()],
-        &crate::ID
-    );
+    let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_account_info.creator, multisig_id);
 
     if *multisig_account.key() != multisig_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidMultisigAccount.into());
     }
 
-    // Validate proposer is a member of the multisig
-    let mut is_multisig_member = false;
-    for member in multisig_account_info_mut.member_keys {
-        if *proposer.key() == member {
-            is_multisig_member = true;
-            break;
-        }
+    // The vault's proposal expiry caps how long a proposal stays open for voting
+    if voting_deadline.saturating_sub(current_time) > multisig_account_info.proposal_expiry {
+        msg!("Voting deadline is beyond the vault's proposal expiry");
+        return Err(TreasuryError::InvalidVotingDeadline.into());
     }
 
-    if !is_multisig_member {
-        return Err(ProgramError::InvalidInstructionData);
+    let member_slots = multisig_account_info.member_slots as usize;
+    let members = MultiSignatureVault::members(multisig_account)?;
+
+    // Validate proposer is a member of the multisig
+    let Some(proposer_slot) = MultiSignatureVault::member_slot(&members, proposer.key()) else {
+        return Err(TreasuryError::NotMultisigMember.into());
+    };
+
+    if !members[proposer_slot].has_role(stream_type.proposer_role()) {
+        msg!("Proposer does not hold the role required for this proposal type");
+        return Err(TreasuryError::MissingMemberRole.into());
     }
 
-    if required_threshold == 0 || required_threshold as u64 > multisig_account_info_mut.member_count {
-        return Err(ProgramError::InvalidInstructionData);
+    let action = validate_payload(&stream_type, payload, &multisig_account_info, &members)?;
+
+    // The vault's policy for this proposal type and amount decides how much weight must approve
+    let required_threshold = multisig_account_info.required_threshold(&stream_type, &action)?;
+
+    if required_threshold == 0 || required_threshold > multisig_account_info.total_weight {
+        return Err(TreasuryError::InvalidThreshold.into());
     }
 
-    let (stream_proposal_account_pda, bump) = pubkey::find_program_address(
-        &[b"stream_proposal", proposal_id.to_le_bytes().as_ref(), multisig_id.to_le_bytes().as_ref()],
-        &crate::ID
-    );
+    // Proposals are numbered by the vault, so their addresses can be enumerated
+    let proposal_id = multisig_account_info.total_proposals;
+
+    drop(members);
+    drop(multisig_account_info);
+
+    let (stream_proposal_account_pda, bump) = pda::find_stream_proposal(multisig_account.key(), proposal_id);
 
     if *stream_proposal_account.key() != stream_proposal_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidProposalAccount.into());
     };
 
     if stream_proposal_account.data_is_empty() {
-        let lamports = Rent::get()?.minimum_balance(StreamProposal::SIZE);
+        let space = StreamProposal::space(payload.len(), member_slots);
+        let lamports = Rent::get()?.minimum_balance(space);
 
         let proposal_id_ref = proposal_id.to_le_bytes();
-        let multisig_id_ref = multisig_id.to_le_bytes();
         let bump_ref = &[bump];
         let seeds = seeds!(
-            b"stream_proposal",
+            pda::STREAM_PROPOSAL_SEED,
+            multisig_account.key().as_ref(),
             proposal_id_ref.as_ref(),
-            multisig_id_ref.as_ref(),
             bump_ref
         );
         let signer_seeds = Signer::from(&seeds);
@@ -114,11 +131,11 @@ pub fn process_create_stream_proposal(accounts: &[AccountInfo], instruction_data
             from: proposer,
             to: stream_proposal_account,
             lamports,
-            space: StreamProposal::SIZE as u64,
+            space: space as u64,
             owner: &crate::ID
         }.invoke_signed(&[signer_seeds])?;
 
-        let mut stream_proposal_account_info = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+        let mut stream_proposal_account_info = StreamProposal::initialize(stream_proposal_account)?;
         
         stream_proposal_account_info.proposer = *proposer.key();
         stream_proposal_account_info.stream_name = stream_name;
@@ -128,18 +145,97 @@ pub fn process_create_stream_proposal(accounts: &[AccountInfo], instruction_data
         stream_proposal_account_info.stream_type = stream_type_raw;
         stream_proposal_account_info.created_at = current_time;
         stream_proposal_account_info.voting_deadline = voting_deadline;
-        stream_proposal_account_info.approvals = [Pubkey::default(); 10];
-        stream_proposal_account_info.approval_count = 0;
-        stream_proposal_account_info.rejections = [Pubkey::default(); 10];
-        stream_proposal_account_info.rejection_count = 0;
+        stream_proposal_account_info.executable_after = 0;
+        stream_proposal_account_info.executed_by = Pubkey::default();
+        stream_proposal_account_info.executed_at = 0;
+        stream_proposal_account_info.approval_weight = 0;
+        stream_proposal_account_info.rejection_weight = 0;
         stream_proposal_account_info.total_vote_count = 0;
         stream_proposal_account_info.required_threshold = required_threshold;
         stream_proposal_account_info.status = ProposalStatus::Active as u8;
+        stream_proposal_account_info.allow_below_minimum = allow_below_minimum;
+        stream_proposal_account_info.payload_len = payload.len() as u16;
+        stream_proposal_account_info.vote_bitmap_len = StreamProposal::bitmap_len(member_slots) as u16;
+        drop(stream_proposal_account_info);
+
+        stream_proposal_account.try_borrow_mut_data()?[StreamProposal::SIZE..StreamProposal::SIZE + payload.len()].copy_from_slice(payload);
         
+        let mut multisig_account_info_mut = MultiSignatureVault::from_account_info_mut(multisig_account)?;
         multisig_account_info_mut.active_proposals = multisig_account_info_mut.active_proposals.checked_add(1).unwrap();
+        multisig_account_info_mut.total_proposals = proposal_id.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
     } else {
         return Err(ProgramError::AccountAlreadyInitialized);
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+fn validate_payload(stream_type: &StreamType, payload: &[u8], multisig_info: &MultiSignatureVault, members: &[Member]) -> Result<ProposalAction, ProgramError> {
+    let action = ProposalAction::try_from_payload(stream_type, payload).map_err(|_| {
+        msg!("Proposal payload does not match the stream type");
+        TreasuryError::InvalidProposalPayload
+    })?;
+
+    match &action {
+        ProposalAction::RemoveMember(member) if !MultiSignatureVault::is_member(members, member) => {
+            msg!("Member to remove is not part of the multisig");
+            return Err(TreasuryError::MemberNotFound.into());
+        },
+        ProposalAction::RemoveMember(member) if MultiSignatureVault::member_slot(members, member)
+            .is_some_and(|slot| !MultiSignatureVault::keeps_role_holders(members, slot, &Member::new(Pubkey::default(), 0, 0))) => {
+            msg!("Removing the member would leave no member to propose or execute");
+            return Err(TreasuryError::MissingRoleHolder.into());
+        },
+        ProposalAction::ConfigChange(config) if config.validate(multisig_info.total_weight).is_err() => {
+            msg!("Invalid configuration: thresholds must be within the total member weight, expiry positive and timelock non-negative");
+            return Err(TreasuryError::InvalidConfig.into());
+        },
+        ProposalAction::SetGuardian(payload) if payload.pause_threshold > multisig_info.total_weight => {
+            msg!("Pause threshold exceeds the total member weight");
+            return Err(TreasuryError::InvalidConfig.into());
+        },
+        ProposalAction::AddMember(payload) if MultiSignatureVault::is_member(members, &payload.member) || payload.member == multisig_info.admin => {
+            msg!("Member to add is already part of the multisig or its admin");
+            return Err(TreasuryError::MemberAlreadyExists.into());
+        },
+        ProposalAction::TransferAdmin(new_admin) if MultiSignatureVault::is_member(members, new_admin) => {
+            msg!("A member cannot become the admin");
+            return Err(TreasuryError::AdminCannotBeMember.into());
+        },
+        ProposalAction::SetMemberWeight(payload) => validate_member_update(multisig_info, members, &payload.member, |member| {
+            Member::new(member.key, payload.weight, member.roles)
+        })?,
+        ProposalAction::SetMemberRoles(payload) => validate_member_update(multisig_info, members, &payload.member, |member| {
+            Member::new(member.key, member.weight, payload.roles)
+        })?,
+        _ => {}
+    }
+
+    Ok(action)
+}
+
+fn validate_member_update(
+    multisig_info: &MultiSignatureVault,
+    members: &[Member],
+    key: &Pubkey,
+    update: impl FnOnce(&Member) -> Member,
+) -> ProgramResult {
+    let Some(slot) = MultiSignatureVault::member_slot(members, key) else {
+        msg!("Member to update is not part of the multisig");
+        return Err(TreasuryError::MemberNotFound.into());
+    };
+
+    let updated = update(&members[slot]);
+
+    if multisig_info.max_threshold() > multisig_info.total_weight_with(members, slot, &updated)? {
+        msg!("Member update would leave the vault threshold unreachable");
+        return Err(TreasuryError::InvalidThreshold.into());
+    }
+
+    if !MultiSignatureVault::keeps_role_holders(members, slot, &updated) {
+        msg!("Member update would leave no member to propose or execute");
+        return Err(TreasuryError::MissingRoleHolder.into());
+    }
+
+    Ok(())
+}
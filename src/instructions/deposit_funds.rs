@@ -6,39 +6,38 @@ use pinocchio::{
     sysvars::{clock::Clock, rent::Rent, Sysvar}, 
     *
 };
+use pinocchio_log::log;
+use pinocchio_system::instructions::CreateAccount;
 use pinocchio_token::state::{Mint, TokenAccount};
 use pinocchio_token::instructions::TransferChecked;
 
-use crate::states::MultiSignatureVault;
+use crate::{error::TreasuryError, pda, instructions::execute_proposal::{mark_proposal_executed, verify_governed_proposal}, states::{DepositReceipt, MultiSignatureVault, ProposalAction, StreamProposal, StreamType}};
 
+/// Deposits into the treasury from any depositor, adding the amount to their receipt, which
+/// the depositor pays for on their first deposit. While the vault's deposit allowlist is on,
+/// the receipt must already exist and be allowed.
 pub fn deposit_funds_to_treasury(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    let [admin, mint, source_token_account, treasury_vault, multisig_account, token_program, system_program] = accounts else {
+    let [depositor, mint, source_token_account, treasury_vault, multisig_account, deposit_receipt, token_program, system_program] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    if !admin.is_signer() {
+    if !depositor.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    if *token_program.key() != pinocchio_token::ID {
+    if *token_program.key() != pinocchio_token::ID || *system_program.key() != pinocchio_system::ID {
         return Err(ProgramError::IncorrectProgramId);
     }
-    
-    let multisig_id = u64::from_le_bytes(
-        instruction_data[0..8]
-            .try_into()
-            .map_err(|_| ProgramError::InvalidInstructionData)?
-    );
 
     let amount = u64::from_le_bytes(
-        instruction_data[8..16]
+        instruction_data
             .try_into()
             .map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
     if amount == 0 {
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidAmount.into());
     }
 
     if multisig_account.data_is_empty() {
@@ -46,77 +45,201 @@ pub fn deposit_funds_to_treasury(accounts: &[AccountInfo], instruction_data: &[u
     }
 
     let multisig_account_info = MultiSignatureVault::from_account_info(multisig_account)?;
-    if multisig_account_info.admin != *admin.key() {
-        return Err(ProgramError::InvalidAccountData);
-    }
 
-    let (multisig_info_pda, _) = pubkey::find_program_address(
-        &[
-            b"multisig_info", 
-            admin.key().as_ref(), 
-            multisig_id.to_le_bytes().as_ref()
-        ],
-        &crate::ID
-    );
+    let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_account_info.creator, multisig_account_info.id);
 
     if *multisig_account.key() != multisig_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidMultisigAccount.into());
     }
 
-    let (treasury_vault_pda, _) = pubkey::find_program_address(
-        &[
-            b"multisig_vault", 
-            mint.key().as_ref(), 
-            multisig_id.to_le_bytes().as_ref()
-        ],
-        &crate::ID
-    );
-
-    if *treasury_vault.key() != treasury_vault_pda {
-        return Err(ProgramError::InvalidAccountData);
+    if *treasury_vault.key() != multisig_account_info.treasury_vault {
+        return Err(TreasuryError::InvalidTreasuryVault.into());
     }
 
     let source_token_account_info = TokenAccount::from_account_info(source_token_account)?;
     if !source_token_account_info.is_initialized() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::UninitializedAccount);
     }
 
     let treasury_vault_account_info = TokenAccount::from_account_info(treasury_vault)?;
     if !treasury_vault_account_info.is_initialized() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::UninitializedAccount);
     }
 
-    if *source_token_account_info.owner() != *admin.key() {
-        return Err(ProgramError::InvalidAccountData);
+    if *source_token_account_info.owner() != *depositor.key() {
+        return Err(TreasuryError::TokenAccountMismatch.into());
     }
 
     if *treasury_vault_account_info.owner() != multisig_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidTreasuryVault.into());
     }
 
     if *source_token_account_info.mint() != *mint.key() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::TokenAccountMismatch.into());
     }
 
     if *treasury_vault_account_info.mint() != *mint.key() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidTreasuryVault.into());
     }
 
     if source_token_account_info.amount() < amount {
         return Err(ProgramError::InsufficientFunds);
     }
 
-    let mint_account_info = Mint::from_account_info(mint)?;
+    let (deposit_receipt_pda, deposit_receipt_bump) = pda::find_deposit_receipt(multisig_account.key(), depositor.key());
+
+    if *deposit_receipt.key() != deposit_receipt_pda {
+        return Err(TreasuryError::InvalidDepositReceipt.into());
+    }
+
+    let deposit_allowlist = multisig_account_info.deposit_allowlist;
+    drop(multisig_account_info);
+
+    if deposit_receipt.data_is_empty() {
+        check_depositor_allowed(deposit_allowlist, None)?;
+
+        create_deposit_receipt(depositor, deposit_receipt, multisig_account, depositor.key(), deposit_receipt_bump)?;
+    } else {
+        check_depositor_allowed(deposit_allowlist, Some(&*DepositReceipt::from_account_info(deposit_receipt)?))?;
+    }
+
+    let decimals = Mint::from_account_info(mint)?.decimals();
 
     TransferChecked {
         from: source_token_account,
         mint,
         to: treasury_vault,
-        authority: admin,
+        authority: depositor,
         amount,
-        decimals: mint_account_info.decimals(),
+        decimals,
     }.invoke()?;
 
+    DepositReceipt::from_account_info_mut(deposit_receipt)?.record_deposit(amount, Clock::get()?.unix_timestamp)?;
+
+    log!("Deposited {} into the treasury", amount);
+
+    Ok(())
+}
+
+/// While the allowlist is on, only depositors whose existing `receipt` is allowed may deposit.
+fn check_depositor_allowed(deposit_allowlist: bool, receipt: Option<&DepositReceipt>) -> ProgramResult {
+    if deposit_allowlist && !receipt.is_some_and(|receipt| receipt.is_allowed) {
+        msg!("Depositor is not on the treasury's deposit allowlist");
+        return Err(TreasuryError::DepositorNotAllowed.into());
+    }
+
+    Ok(())
+}
+
+pub fn process_set_deposit_allowlist(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::SetDepositAllowlist)?;
+
+    let ProposalAction::SetDepositAllowlist(enabled) = ProposalAction::try_from_payload(&StreamType::SetDepositAllowlist, &StreamProposal::payload(stream_proposal_account)?)? else {
+        return Err(TreasuryError::InvalidProposalPayload.into());
+    };
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+
+    multisig_info.deposit_allowlist = enabled;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    msg!(if enabled { "Deposit allowlist enabled!" } else { "Deposit allowlist disabled!" });
+
+    Ok(())
+}
+
+/// Executes an approved `AllowDepositor` proposal, creating the depositor's receipt at the
+/// executor's expense if they have never deposited.
+pub fn process_allow_depositor(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account, deposit_receipt, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::AllowDepositor)?;
+
+    let ProposalAction::AllowDepositor(payload) = ProposalAction::try_from_payload(&StreamType::AllowDepositor, &StreamProposal::payload(stream_proposal_account)?)? else {
+        return Err(TreasuryError::InvalidProposalPayload.into());
+    };
+
+    let (deposit_receipt_pda, deposit_receipt_bump) = pda::find_deposit_receipt(multisig_account.key(), &payload.depositor);
+
+    if *deposit_receipt.key() != deposit_receipt_pda {
+        return Err(TreasuryError::InvalidDepositReceipt.into());
+    }
+
+    if deposit_receipt.data_is_empty() {
+        create_deposit_receipt(executor, deposit_receipt, multisig_account, &payload.depositor, deposit_receipt_bump)?;
+    }
+
+    DepositReceipt::from_account_info_mut(deposit_receipt)?.is_allowed = payload.allowed;
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    msg!(if payload.allowed { "Depositor allowed!" } else { "Depositor disallowed!" });
+
     Ok(())
 }
 
+fn create_deposit_receipt(payer: &AccountInfo, deposit_receipt: &AccountInfo, multisig_account: &AccountInfo, depositor: &Pubkey, bump: u8) -> ProgramResult {
+    let lamports = Rent::get()?.minimum_balance(DepositReceipt::SIZE);
+
+    let bump_ref = &[bump];
+    let seeds = seeds!(
+        pda::DEPOSIT_RECEIPT_SEED,
+        multisig_account.key().as_ref(),
+        depositor.as_ref(),
+        bump_ref
+    );
+    let signer_seeds = Signer::from(&seeds);
+
+    CreateAccount {
+        from: payer,
+        to: deposit_receipt,
+        lamports,
+        space: DepositReceipt::SIZE as u64,
+        owner: &crate::ID
+    }.invoke_signed(&[signer_seeds])?;
+
+    let mut receipt = DepositReceipt::initialize(deposit_receipt)?;
+    receipt.multisig = *multisig_account.key();
+    receipt.depositor = *depositor;
+    receipt.total_deposited = 0;
+    receipt.deposit_count = 0;
+    receipt.first_deposit_at = 0;
+    receipt.last_deposit_at = 0;
+    receipt.is_allowed = false;
+    receipt.bump = bump;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_allowlist_rejects_depositors_not_allowed() {
+        let mut receipt: DepositReceipt = unsafe { core::mem::zeroed() };
+
+        assert_eq!(check_depositor_allowed(true, None), Err(TreasuryError::DepositorNotAllowed.into()));
+        assert_eq!(check_depositor_allowed(true, Some(&receipt)), Err(TreasuryError::DepositorNotAllowed.into()));
+        assert_eq!(check_depositor_allowed(false, None), Ok(()));
+        assert_eq!(check_depositor_allowed(false, Some(&receipt)), Ok(()));
+
+        receipt.is_allowed = true;
+        assert_eq!(check_depositor_allowed(true, Some(&receipt)), Ok(()));
+    }
+}
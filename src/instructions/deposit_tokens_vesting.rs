@@ -1,47 +1,44 @@
 use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, *};
 use pinocchio_token::{instructions::TransferChecked, state::{Mint, TokenAccount}};
 
-use crate::states::VestingContractInfo;
+use crate::{error::TreasuryError, pda, states::VestingContractInfo};
 
 pub fn process_deposit_token_vesting_vault(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
     let [creator, mint, vesting_contract_info, vault, company_token_account, system_program, token_program] = accounts else {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     if !creator.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::MissingRequiredSignature);
     };
 
     if *mint.owner() != pinocchio_token::ID {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidMint.into());
+    };
+
+    if instruction_data.len() < 40 {
+        return Err(ProgramError::InvalidInstructionData);
     };
 
     let mut company_name = [0u8; 32];
     company_name.copy_from_slice(&instruction_data[0..32]);
 
     let deposit_amount = u64::from_le_bytes(
-        instruction_data[32..40].try_into().map_err(|_| ProgramError::InvalidAccountData)?
+        instruction_data[32..40].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
     let mut vesting_contract_account_info = VestingContractInfo::from_account_info_mut(vesting_contract_info)?;
 
-    let (vesting_contract_info_pda, vesting_contract_bump) = pubkey::find_program_address(
-        &[company_name.as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
+    let (vesting_contract_info_pda, vesting_contract_bump) = pda::find_vesting_contract(&company_name, creator.key());
 
     if *vesting_contract_info.key() != vesting_contract_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let (vault_pda, vault_bump) = pubkey::find_program_address(
-        &[b"vault", mint.key().as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
-
-    if *vault.key() != vault_pda {
-        return Err(ProgramError::InvalidAccountData);
+    // Contracts created before vaults were seeded per contract keep their original vault.
+    if *vault.key() != vesting_contract_account_info.vault_account {
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
     let company_token_account_info = TokenAccount::from_account_info(company_token_account)?;
@@ -51,7 +48,7 @@ pub fn process_deposit_token_vesting_vault(accounts: &[AccountInfo], instruction
     };
 
     if *company_token_account_info.owner() != *creator.key() {
-        return Err(ProgramError::IllegalOwner);
+        return Err(TreasuryError::TokenAccountMismatch.into());
     };
 
     let mint_account_info = Mint::from_account_info(mint)?;
@@ -0,0 +1,110 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, Sysvar}, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, pda, instructions::{cancel_proposal::consenting_weight, execute_proposal::{mark_proposal_executed, verify_governed_proposal}}, states::{GuardianPayload, MultiSignatureVault, StreamProposal, StreamType}};
+
+/// Pauses the vault, freezing proposal payouts, withdrawals from treasury-funded streams and
+/// claims on treasury-funded vesting. The guardian may pause alone; otherwise the authority
+/// and the members signing as the accounts after `multisig_account` must together hold
+/// `pause_threshold` voting weight.
+pub fn process_emergency_pause(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [authority, multisig_account, consenting_members @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    let (is_guardian, pause_threshold) = {
+        let multisig_info = MultiSignatureVault::from_account_info(multisig_account)?;
+
+        let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_info.creator, multisig_info.id);
+
+        if *multisig_account.key() != multisig_info_pda {
+            return Err(TreasuryError::InvalidMultisigAccount.into());
+        };
+
+        if !multisig_info.is_active {
+            msg!("Vault is already paused");
+            return Err(TreasuryError::MultisigInactive.into());
+        };
+
+        (multisig_info.guardian != Pubkey::default() && *authority.key() == multisig_info.guardian, multisig_info.pause_threshold)
+    };
+
+    if !is_guardian {
+        if pause_threshold == 0 {
+            return Err(TreasuryError::PauseNotAllowed.into());
+        };
+
+        let members = MultiSignatureVault::members(multisig_account)?;
+        let consent = consenting_weight(&members, authority, consenting_members)?;
+
+        if consent < pause_threshold {
+            log!("Pausing needs {} voting weight, got {}", pause_threshold, consent);
+            return Err(TreasuryError::PauseNotAllowed.into());
+        };
+    };
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    multisig_info.is_active = false;
+    multisig_info.last_updated = Clock::get()?.unix_timestamp;
+
+    msg!("Vault paused!");
+
+    Ok(())
+}
+
+pub fn process_unpause(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::Unpause)?;
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+
+    if multisig_info.is_active {
+        return Err(TreasuryError::VaultNotPaused.into());
+    };
+
+    multisig_info.is_active = true;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    msg!("Vault unpaused!");
+
+    Ok(())
+}
+
+pub fn process_set_guardian(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::SetGuardian)?;
+
+    let payload = GuardianPayload::try_from_bytes(&StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)?;
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+
+    // Member weights may have changed since the proposal was created.
+    if payload.pause_threshold > multisig_info.total_weight {
+        log!("Cannot set pause threshold {} above total weight {}", payload.pause_threshold, multisig_info.total_weight);
+        return Err(TreasuryError::InvalidConfig.into());
+    };
+
+    multisig_info.guardian = payload.guardian;
+    multisig_info.pause_threshold = payload.pause_threshold;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    log!("Guardian updated! Pause threshold: {}", multisig_info.pause_threshold);
+
+    Ok(())
+}
@@ -1,79 +1,101 @@
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, Sysvar}, *};
-use crate::{instructions, states::{MultiSignatureVault, ProposalStatus, StreamProposal, StreamType}};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, Sysvar}, *};
 
+use crate::{error::TreasuryError, pda, instructions::treasury_actions::{execute_batch_payments, execute_stream_payment, execute_token_transfer, execute_vesting, Treasury}, states::{Member, MultiSignatureVault, ProposalAction, ProposalStatus, StreamProposal, StreamType}};
+
+/// Executes the action stored in an approved proposal. The action parameters come from the
+/// proposal payload bound at creation; the accounts passed after `multisig_account` must
+/// match them. Payouts are drawn from the treasury vault.
 pub fn process_execute_proposal(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    let [executor, stream_proposal_account, multisig_account] = accounts else {
-        return Err(ProgramError::InvalidAccountData);
+    let [executor, stream_proposal_account, multisig_account, action_accounts @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    if !executor.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+    if !instruction_data.is_empty() {
+        msg!("Execution parameters are bound to the proposal and cannot be supplied");
+        return Err(TreasuryError::UnexpectedInstructionData.into());
     };
 
-    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
-    let multisig_info = MultiSignatureVault::from_account_info(multisig_account)?;
-    
-    if !can_execute_proposal(&stream_proposal, &multisig_info)? {
-        msg!("Proposal cannot be executed - threshold not met or expired");
-        return Err(ProgramError::InvalidAccountData);
+    let (stream_type, allow_below_minimum) = {
+        let stream_proposal = StreamProposal::from_account_info(stream_proposal_account)?;
+        (StreamType::try_from(&stream_proposal.stream_type)?, stream_proposal.allow_below_minimum)
     };
 
-    verify_multisig_execution(&stream_proposal, &multisig_info, executor.key())?;
-
-    let stream_type = StreamType::try_from(&stream_proposal.stream_type)?;
-
-    match stream_type {
-        StreamType::BatchPayments => {
-            instructions::init_batch_payments::process_initialize_batch_state(accounts, instruction_data)?;
-            instructions::batch_payments::process_batch_transfer(accounts, instruction_data)?;
-
-            stream_proposal.status = ProposalStatus::Executed as u8;
-        },
-        StreamType::TokenTransfers => {
-            instructions::token_transfers::process_transfer_tokens(accounts, instruction_data)?;
-            
-            stream_proposal.status = ProposalStatus::Executed as u8;
-        },
-        StreamType::PaymentStreaming => {
-            instructions::init_stream_payments::process_init_stream_payment(accounts, instruction_data)?;
-
-            stream_proposal.status = ProposalStatus::Active as u8;
-        },
-        StreamType::Vesting => {
-            instructions::init_vesting::process_init_vesting_contract(accounts, instruction_data)?;
-            instructions::init_beneficiary::process_init_beneficiary(accounts, instruction_data)?;
-
-            stream_proposal.status = ProposalStatus::Active as u8;
-        }
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, stream_type.clone())?;
+
+    let action = ProposalAction::try_from_payload(&stream_type, &StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)?;
+
+    // Only payouts carry a mint; every other action has its own instruction.
+    let Some(&action_mint) = action.mint() else {
+        msg!("Governance proposals are executed through their dedicated instruction");
+        return Err(TreasuryError::DedicatedInstructionRequired.into());
+    };
+
+    let [treasury_vault, mint, action_accounts @ ..] = action_accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if action_mint != *mint.key() {
+        msg!("Mint does not match the approved proposal");
+        return Err(TreasuryError::MintMismatch.into());
+    };
+
+    let treasury = Treasury::load(multisig_account, treasury_vault, mint, allow_below_minimum)?;
+
+    if allow_below_minimum {
+        msg!("Emergency proposal: treasury minimum balance is not enforced");
     }
 
+    // Fail before creating any stream/vesting accounts; every transfer re-checks the floor.
+    treasury.check_minimum_balance(action.total_outflow()?)?;
+
+    match action {
+        ProposalAction::BatchPayments(payload) => execute_batch_payments(&treasury, &payload, action_accounts)?,
+        ProposalAction::TokenTransfers(payload) => execute_token_transfer(&treasury, &payload, action_accounts)?,
+        ProposalAction::PaymentStreaming(payload) => execute_stream_payment(executor, &treasury, &payload, action_accounts)?,
+        ProposalAction::Vesting(payload) => execute_vesting(executor, &treasury, &payload, action_accounts)?,
+        _ => return Err(TreasuryError::DedicatedInstructionRequired.into()),
+    }
+
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
     msg!("Stream proposal executed successfully!");
 
     Ok(())
 }
 
-fn can_execute_proposal(
+/// Tallies `votes` with the current member weights against the vault's current threshold for
+/// the proposal's type and amount tier, so weight and policy changes since the last vote apply.
+pub fn can_execute_proposal(
     proposal: &StreamProposal, 
-    multisig_info: &MultiSignatureVault
+    action: &ProposalAction,
+    votes: &[u8],
+    multisig_info: &MultiSignatureVault,
+    members: &[Member],
 ) -> Result<bool, ProgramError> {
     let current_time = Clock::get()?.unix_timestamp;
     
-    if current_time > proposal.voting_deadline {
+    if current_time > proposal.execution_deadline() {
         return Ok(false);
     }
 
-    if !multisig_info.is_active {
+    if !multisig_info.is_active && action.mint().is_some() {
+        msg!("Vault is paused");
         return Ok(false);
     }
 
-    let required_threshold = multisig_info.threshold as u8;
+    let (approval_weight, rejection_weight) = proposal.tally(votes, members);
     
-    if proposal.approval_count < required_threshold {
+    let stream_type = StreamType::try_from(&proposal.stream_type)?;
+
+    if approval_weight < multisig_info.required_threshold(&stream_type, action)? {
         return Ok(false);
     }
 
-    if proposal.approval_count <= proposal.rejection_count {
+    if approval_weight <= rejection_weight {
         return Ok(false);
     }
 
@@ -85,41 +107,104 @@ fn can_execute_proposal(
     Ok(true)
 }
 
-fn verify_multisig_execution(
+pub fn verify_multisig_execution(
     proposal: &StreamProposal,
+    votes: &[u8],
     multisig_info: &MultiSignatureVault,
+    members: &[Member],
     executor_key: &Pubkey,
 ) -> ProgramResult {
-    let mut is_multisig_member = false;
-    for member in multisig_info.member_keys {
-        if member == *executor_key {
-            is_multisig_member = true;
-            break;
-        }
-    }
-
-    if !is_multisig_member {
+    let Some(executor_slot) = MultiSignatureVault::member_slot(members, executor_key) else {
         msg!("Executor is not a multisig member");
-        return Err(ProgramError::Custom(2005)); 
-    }
+        return Err(TreasuryError::NotMultisigMember.into());
+    };
 
-    let mut executor_approved = false;
-    for i in 0..proposal.approval_count as usize {
-        if proposal.approvals[i] == *executor_key {
-            executor_approved = true;
-            break;
-        }
+    if !members[executor_slot].has_role(Member::EXECUTOR) {
+        msg!("Executor does not hold the executor role");
+        return Err(TreasuryError::MissingMemberRole.into());
     }
 
-    if !executor_approved {
+    // Executors who also vote must have approved; a dedicated executor set need not vote.
+    if members[executor_slot].has_role(Member::VOTER) && !proposal.approved_by(votes, executor_slot) {
         msg!("Executor did not approve this proposal");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::ExecutorDidNotApprove.into());
     }
 
     if proposal.multisig_id != multisig_info.id {
         msg!("Proposal multisig_id mismatch");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::MultisigIdMismatch.into());
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Checks that `stream_proposal_account` is an approved `expected_type` proposal belonging to
+/// `multisig_account`, and that `executor` holds the executor role (and approved it, if they vote).
+pub fn verify_governed_proposal(
+    executor: &AccountInfo,
+    stream_proposal_account: &AccountInfo,
+    multisig_account: &AccountInfo,
+    expected_type: StreamType,
+) -> ProgramResult {
+    if !executor.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    if *stream_proposal_account.owner() != crate::ID || *multisig_account.owner() != crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    };
+
+    let multisig_info = MultiSignatureVault::from_account_info(multisig_account)?;
+
+    let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_info.creator, multisig_info.id);
+
+    if *multisig_account.key() != multisig_info_pda {
+        return Err(TreasuryError::InvalidMultisigAccount.into());
+    };
+
+    let stream_proposal = StreamProposal::from_account_info(stream_proposal_account)?;
+
+    let (stream_proposal_pda, _) = pda::find_stream_proposal(multisig_account.key(), stream_proposal.proposal_id);
+
+    if *stream_proposal_account.key() != stream_proposal_pda {
+        return Err(TreasuryError::InvalidProposalAccount.into());
+    };
+
+    if StreamType::try_from(&stream_proposal.stream_type)? != expected_type {
+        msg!("Proposal type does not match the instruction");
+        return Err(TreasuryError::ProposalTypeMismatch.into());
+    };
+
+    if Clock::get()?.unix_timestamp < stream_proposal.executable_after {
+        msg!("Proposal is still in its timelock");
+        return Err(TreasuryError::TimelockNotElapsed.into());
+    };
+
+    let action = ProposalAction::try_from_payload(&expected_type, &StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)?;
+    let votes = StreamProposal::votes(stream_proposal_account)?;
+    let members = MultiSignatureVault::members(multisig_account)?;
+
+    if !can_execute_proposal(&stream_proposal, &action, &votes, &multisig_info, &members)? {
+        msg!("Proposal cannot be executed - threshold not met or expired");
+        return Err(TreasuryError::ProposalNotExecutable.into());
+    };
+
+    verify_multisig_execution(&stream_proposal, &votes, &multisig_info, &members, executor.key())
+}
+
+pub fn mark_proposal_executed(proposal: &mut StreamProposal, multisig_info: &mut MultiSignatureVault, executor: &Pubkey) -> ProgramResult {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    proposal.status = ProposalStatus::Executed as u8;
+    proposal.executed_by = *executor;
+    proposal.executed_at = current_time;
+
+    multisig_info.active_proposals = multisig_info.active_proposals.saturating_sub(1);
+    multisig_info.executed_proposals = multisig_info.executed_proposals
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    multisig_info.last_updated = current_time;
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, instructions::close_proposal::{close_proposal, verify_proposal_refund}, states::{MultiSignatureVault, ProposalStatus, StreamProposal}};
+
+/// Marks a proposal still open past its voting deadline, or an approved one past its
+/// execution deadline, as `Expired` and closes it, refunding the rent to the proposer.
+/// Anyone may call it.
+pub fn process_expire_proposal(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [stream_proposal_account, multisig_account, proposer] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_proposal_refund(stream_proposal_account, multisig_account, proposer, true)?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let proposal_id = {
+        let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+
+        let status = ProposalStatus::try_from(&stream_proposal.status)?;
+        if status != ProposalStatus::Active && status != ProposalStatus::Approved {
+            return Err(TreasuryError::ProposalFinalized.into());
+        };
+
+        if current_time <= stream_proposal.execution_deadline() {
+            return Err(TreasuryError::ProposalNotExpired.into());
+        };
+
+        stream_proposal.status = ProposalStatus::Expired as u8;
+        stream_proposal.proposal_id
+    };
+
+    {
+        let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+        multisig_info.active_proposals = multisig_info.active_proposals.saturating_sub(1);
+        multisig_info.last_updated = current_time;
+    }
+
+    close_proposal(stream_proposal_account, proposer, current_time)?;
+
+    log!("Proposal {} expired", proposal_id);
+
+    Ok(())
+}
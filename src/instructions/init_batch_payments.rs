@@ -1,14 +1,14 @@
-use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{rent::Rent, Sysvar}, *};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{rent::Rent, Sysvar}, *};
 use pinocchio_system::instructions::CreateAccount;
 
 use pinocchio_token::{instructions::InitializeAccount3, state::TokenAccount};
 
-use crate::states::BatchState;
+use crate::{error::TreasuryError, pda, states::{BatchState, MAX_RECIPIENTS}};
 
 pub fn process_initialize_batch_state(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
     if accounts.len() < 6 {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     let [creator, mint, batch_state_account, creator_token_account, system_program, token_program] = accounts else {
@@ -25,30 +25,32 @@ pub fn process_initialize_batch_state(accounts: &[AccountInfo], instruction_data
 
     let total_recipients = instruction_data[8];
 
+    if total_recipients == 0 || total_recipients as usize > MAX_RECIPIENTS {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+
     if !creator.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    if *token_program.key() != pinocchio_token::ID || *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
     };
 
     if *mint.owner() != pinocchio_token::ID {
-        return Err(ProgramError::InvalidAccountOwner);
+        return Err(TreasuryError::InvalidMint.into());
     };
 
-    let (batch_state_pda, bump) = pubkey::find_program_address(
-        &[b"batch_state", creator.key().as_ref(), mint.key().as_ref(), &batch_id.to_le_bytes()],
-        &crate::ID
-    );
+    let (batch_state_pda, bump) = pda::find_batch_state(creator.key(), mint.key(), batch_id);
 
-    let (creator_token_account_pda, bump2) = pubkey::find_program_address(
-        &[b"creator_token_account", creator.key().as_ref(), mint.key().as_ref()],
-        &crate::ID
-    );
+    let (creator_token_account_pda, bump2) = pda::find_creator_token_account(creator.key(), mint.key());
 
     if *creator_token_account.key() != creator_token_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidBatchAccount.into());
     };
 
     if *batch_state_account.key() != batch_state_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidBatchAccount.into());
     };
 
     if batch_state_account.data_len() == 0 {
@@ -56,15 +58,26 @@ pub fn process_initialize_batch_state(accounts: &[AccountInfo], instruction_data
 
         let lamports = Rent::get()?.minimum_balance(BatchState::LEN);
 
+        let batch_id_ref = batch_id.to_le_bytes();
+        let bump_ref = &[bump];
+        let seeds = seeds!(
+            pda::BATCH_STATE_SEED,
+            creator.key().as_ref(),
+            mint.key().as_ref(),
+            batch_id_ref.as_ref(),
+            bump_ref
+        );
+        let signer_seeds = Signer::from(&seeds);
+
         CreateAccount {
             from: creator,
             to: batch_state_account,
             lamports,
             space: BatchState::LEN as u64,
             owner: &crate::ID
-        }.invoke()?;
+        }.invoke_signed(&[signer_seeds])?;
 
-        let mut batch_state_account_mut = BatchState::from_account_info_mut(batch_state_account)?;
+        let mut batch_state_account_mut = BatchState::initialize(batch_state_account)?;
         batch_state_account_mut.batch_id = batch_id;
         batch_state_account_mut.authority = *creator.key();
         batch_state_account_mut.mint = *mint.key();
@@ -84,13 +97,22 @@ pub fn process_initialize_batch_state(accounts: &[AccountInfo], instruction_data
 
         let lamports = Rent::get()?.minimum_balance(TokenAccount::LEN);
 
+        let bump_ref = &[bump2];
+        let seeds = seeds!(
+            pda::CREATOR_TOKEN_ACCOUNT_SEED,
+            creator.key().as_ref(),
+            mint.key().as_ref(),
+            bump_ref
+        );
+        let signer_seeds = Signer::from(&seeds);
+
         CreateAccount {
             from: creator,
             to: creator_token_account,
-            lamports: lamports,
+            lamports,
             space: TokenAccount::LEN as u64,
             owner: &pinocchio_token::id()
-        }.invoke()?;
+        }.invoke_signed(&[signer_seeds])?;
 
         InitializeAccount3 {
             account: creator_token_account,
@@ -103,7 +125,7 @@ pub fn process_initialize_batch_state(accounts: &[AccountInfo], instruction_data
     } else {
         let token_account_data = TokenAccount::from_account_info(creator_token_account)?;
         if *token_account_data.mint() != *mint.key() || *token_account_data.owner() != *creator.key() {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(TreasuryError::TokenAccountMismatch.into());
         }
 
         msg!("Creator token account already exists and is valid");
@@ -156,11 +178,8 @@ fn test_process_initialize_batch_transfer() {
         rent_epoch: 0,
     };
 
-     // Most programs expect an instruction discriminator first
-    // You'll need to check your instruction enum to see what discriminator to use
-    // This assumes InitializeBatchState is the first variant (discriminator = 0)
     let mut instruction_data = vec![0u8; 17];
-    instruction_data[0] = 0; // instruction discriminator for InitializeBatchState
+    instruction_data[0] = 13; // instruction discriminator for InitializeBatchState
     instruction_data[1..9].copy_from_slice(&BATCH_ID.to_le_bytes());
     instruction_data[9] = 2; // total_recipients
 
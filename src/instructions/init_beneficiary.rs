@@ -2,7 +2,7 @@ use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::P
 use pinocchio_system::instructions::CreateAccount;
 use pinocchio_token::{instructions::InitializeAccount3, state::TokenAccount};
 
-use crate::states::{VestingContractInfo, VestingRecordInfo};
+use crate::{error::TreasuryError, pda, states::{VestingContractInfo, VestingRecordInfo}};
 
 pub fn process_init_beneficiary(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
@@ -14,6 +14,18 @@ pub fn process_init_beneficiary(accounts: &[AccountInfo], instruction_data: &[u8
         return Err(ProgramError::MissingRequiredSignature);
     };
 
+    if *token_program.key() != pinocchio_token::ID || *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    if *mint.owner() != pinocchio_token::ID {
+        return Err(TreasuryError::InvalidMint.into());
+    };
+
+    if *vesting_contract_info.owner() != crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    };
+
     if instruction_data.len() < 64 {
         return Err(ProgramError::InvalidInstructionData);
     }
@@ -39,64 +51,58 @@ pub fn process_init_beneficiary(accounts: &[AccountInfo], instruction_data: &[u8
 
     if start_time >= end_time {
         msg!("Start time must be before end time");
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidSchedule.into());
     }
 
     if vesting_amount == 0 {
         msg!("Vesting amount cannot be zero");
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidAmount.into());
     }
 
     if cliff_period < 0 {
         msg!("Cliff period cannot be negative");
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidSchedule.into());
     }
 
-    let (vesting_contract_info_pda, vesting_contract_bump) = pubkey::find_program_address(
-        &[company_name.as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
+    let (vesting_contract_info_pda, vesting_contract_bump) = pda::find_vesting_contract(&company_name, creator.key());
 
     if *vesting_contract_info.key() != vesting_contract_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let (vesting_record_info_pda, vesting_record_info_bump) = pubkey::find_program_address(
-        &[b"vesting_record_info", vesting_contract_info.key().as_ref(), beneficiary.key().as_ref()],
-        &crate::ID
-    );
+    let (vesting_record_info_pda, vesting_record_info_bump) = pda::find_vesting_record(vesting_contract_info.key(), beneficiary.key());
 
     if *vesting_record_info.key() != vesting_record_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let (beneficiary_token_account_pda, beneficiary_token_account_pda_bump) = pubkey::find_program_address(
-        &[beneficiary.key().as_ref(), mint.key().as_ref()],
-        &crate::ID
-    );
+    let (beneficiary_token_account_pda, beneficiary_token_account_pda_bump) = pda::find_beneficiary_token_account(beneficiary.key(), mint.key());
 
     if *beneficiary_token_account.key() != beneficiary_token_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let mut vesting_contract_info_account_mut = VestingContractInfo::from_account_info_mut(vesting_contract_info)?;
+    if !vesting_record_info.data_is_empty() {
+        msg!("Beneficiary already has a vesting record");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    };
 
-    let beneficiary_token_account_info = TokenAccount::from_account_info(beneficiary_token_account)?;
+    let mut vesting_contract_info_account_mut = VestingContractInfo::from_account_info_mut(vesting_contract_info)?;
 
-    if *mint.owner() != pinocchio_token::ID {
-        return Err(ProgramError::InvalidAccountData);
+    if vesting_contract_info_account_mut.creator != *creator.key() {
+        return Err(TreasuryError::NotVestingCreator.into());
     }
 
-    if vesting_contract_info_account_mut.creator != *creator.key() {
-        return Err(ProgramError::InvalidAccountData);
+    if vesting_contract_info_account_mut.mint != *mint.key() {
+        return Err(TreasuryError::MintMismatch.into());
     }
 
     if !vesting_contract_info_account_mut.is_active {
         msg!("Vesting contract is not active");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::VestingInactive.into());
     }
 
-    if !beneficiary_token_account_info.is_initialized() {
+    if beneficiary_token_account.data_is_empty() {
         msg!("Initializing Beneficiary Token Account");
 
         let lamports = Rent::get()?.minimum_balance(TokenAccount::LEN);
@@ -122,47 +128,55 @@ pub fn process_init_beneficiary(accounts: &[AccountInfo], instruction_data: &[u8
             mint,
             owner: beneficiary.key(), 
         }.invoke()?;
-    }
-
-    if vesting_record_info.data_is_empty() {
-        msg!("Creating Vesting Record Info Account!");
+    } else {
+        let beneficiary_token_account_info = TokenAccount::from_account_info(beneficiary_token_account)?;
 
-        let lamports = Rent::get()?.minimum_balance(VestingRecordInfo::SIZE);
-
-        let bump = &[vesting_record_info_bump];
-        let seeds = seeds!(
-            b"vesting_record_info", 
-            vesting_contract_info.key().as_ref(), 
-            beneficiary.key().as_ref(),
-            bump
-        );
-        let signer_seeds = Signer::from(&seeds);
+        if beneficiary_token_account_info.owner() != beneficiary.key() || beneficiary_token_account_info.mint() != mint.key() {
+            return Err(TreasuryError::TokenAccountMismatch.into());
+        };
+    }
 
-        CreateAccount {
-            from: creator,
-            to: vesting_record_info,
-            lamports,
-            space: VestingRecordInfo::SIZE as u64,
-            owner: &crate::ID
-        }.invoke_signed(&[signer_seeds])?;
+    msg!("Creating Vesting Record Info Account!");
 
-        let mut vesting_record_info_mut = VestingRecordInfo::from_account_info_mut(vesting_record_info)?;
-        vesting_record_info_mut.beneficiary = *beneficiary.key();
-        vesting_record_info_mut.mint = *mint.key();
-        vesting_record_info_mut.total_vested_tokens = vesting_amount;
-        vesting_record_info_mut.total_claimed_tokens_by_beneficiary = 0;
-        vesting_record_info_mut.cliff_period = cliff_period;
-        vesting_record_info_mut.vault_bump = vesting_contract_info_account_mut.vault_bump;
-        vesting_record_info_mut.vesting_contract_info_bump = vesting_contract_info_account_mut.bump;
-        vesting_record_info_mut.bump = vesting_record_info_bump;
-        vesting_record_info_mut.beneficiary_ata = *beneficiary_token_account.key();
-        vesting_record_info_mut.has_claimed = false;
-        vesting_record_info_mut.start_time = start_time;
-        vesting_record_info_mut.end_time = end_time;
-    }
+    let lamports = Rent::get()?.minimum_balance(VestingRecordInfo::SIZE);
 
-    vesting_contract_info_account_mut.total_available_tokens += vesting_amount;
-    vesting_contract_info_account_mut.total_locked_tokens += vesting_amount;
+    let bump = &[vesting_record_info_bump];
+    let seeds = seeds!(
+        pda::VESTING_RECORD_SEED,
+        vesting_contract_info.key().as_ref(), 
+        beneficiary.key().as_ref(),
+        bump
+    );
+    let signer_seeds = Signer::from(&seeds);
+
+    CreateAccount {
+        from: creator,
+        to: vesting_record_info,
+        lamports,
+        space: VestingRecordInfo::SIZE as u64,
+        owner: &crate::ID
+    }.invoke_signed(&[signer_seeds])?;
+
+    let mut vesting_record_info_mut = VestingRecordInfo::initialize(vesting_record_info)?;
+    vesting_record_info_mut.beneficiary = *beneficiary.key();
+    vesting_record_info_mut.mint = *mint.key();
+    vesting_record_info_mut.total_vested_tokens = vesting_amount;
+    vesting_record_info_mut.total_claimed_tokens_by_beneficiary = 0;
+    vesting_record_info_mut.cliff_period = cliff_period;
+    vesting_record_info_mut.vault_bump = vesting_contract_info_account_mut.vault_bump;
+    vesting_record_info_mut.vesting_contract_info_bump = vesting_contract_info_account_mut.bump;
+    vesting_record_info_mut.bump = vesting_record_info_bump;
+    vesting_record_info_mut.beneficiary_ata = *beneficiary_token_account.key();
+    vesting_record_info_mut.has_claimed = false;
+    vesting_record_info_mut.start_time = start_time;
+    vesting_record_info_mut.end_time = end_time;
+
+    vesting_contract_info_account_mut.total_available_tokens = vesting_contract_info_account_mut.total_available_tokens
+        .checked_add(vesting_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    vesting_contract_info_account_mut.total_locked_tokens = vesting_contract_info_account_mut.total_locked_tokens
+        .checked_add(vesting_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
 
     Ok(())
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::P
 use pinocchio_system::instructions::CreateAccount;
 use pinocchio_token::{state::TokenAccount, instructions::InitializeAccount3, *};
 
-use crate::states::MultiSignatureVault;
+use crate::{error::TreasuryError, pda, states::{AmountTier, MultiSignatureVault, StreamType}};
 
 pub fn process_init_multisig_vault(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
@@ -38,30 +38,27 @@ pub fn process_init_multisig_vault(accounts: &[AccountInfo], instruction_data: &
         instruction_data[24..32].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
-    if threshold == 0 || threshold > 10 {
-        return Err(ProgramError::InvalidInstructionData);
+    // Optional trailing flag; vaults created without it accept deposits from anyone.
+    let deposit_allowlist = instruction_data.get(32).is_some_and(|flag| *flag != 0);
+
+    if threshold == 0 {
+        return Err(TreasuryError::InvalidThreshold.into());
     }
 
     if proposal_expiry <= 0 {
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidProposalExpiry.into());
     }
 
-    let (multisig_info_pda, multisig_info_bump) = pubkey::find_program_address(
-        &[b"multisig_info", admin.key().as_ref(), multisig_id.to_le_bytes().as_ref()],
-        &crate::ID
-    );
+    let (multisig_info_pda, multisig_info_bump) = pda::find_multisig_info(admin.key(), multisig_id);
 
     if *multisig_info.key() != multisig_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidMultisigAccount.into());
     };
 
-    let (treasury_vault_pda, treasury_vault_bump) = pubkey::find_program_address(
-        &[b"multisig_vault", mint.key().as_ref(), multisig_id.to_le_bytes().as_ref()],
-        &crate::ID
-    );
+    let (treasury_vault_pda, treasury_vault_bump) = pda::find_treasury_vault(&multisig_info_pda, mint.key());
 
     if *treasury_vault.key() != treasury_vault_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidTreasuryVault.into());
     };
 
     if *token_program.key() != pinocchio_token::id() {
@@ -72,11 +69,10 @@ pub fn process_init_multisig_vault(accounts: &[AccountInfo], instruction_data: &
         let lamports = Rent::get()?.minimum_balance(TokenAccount::LEN);
 
         let bump_ref = &[treasury_vault_bump];
-        let multisig_id_ref = multisig_id.to_le_bytes();
         let seeds = seeds!(
-            b"multisig_vault", 
-            mint.key().as_ref(), 
-            multisig_id_ref.as_ref(), 
+            pda::TREASURY_VAULT_SEED,
+            multisig_info_pda.as_ref(),
+            mint.key().as_ref(),
             bump_ref
         );
         let signer_seeds = Signer::from(&seeds);
@@ -104,7 +100,7 @@ pub fn process_init_multisig_vault(accounts: &[AccountInfo], instruction_data: &
         let multisig_id_ref = multisig_id.to_le_bytes();
         let bump_ref = &[multisig_info_bump];
         let seeds = seeds!(
-            b"multisig_info", 
+            pda::MULTISIG_INFO_SEED,
             admin.key().as_ref(), 
             multisig_id_ref.as_ref(),
             bump_ref
@@ -119,16 +115,26 @@ pub fn process_init_multisig_vault(accounts: &[AccountInfo], instruction_data: &
             owner: &crate::ID
         }.invoke_signed(&[signer_seeds])?;
 
-        let mut multi_signature_vault_info = MultiSignatureVault::from_account_info_mut(multisig_info)?;
+        let mut multi_signature_vault_info = MultiSignatureVault::initialize(multisig_info)?;
         let current_time = Clock::get()?.unix_timestamp;
 
         multi_signature_vault_info.id = multisig_id;
         multi_signature_vault_info.admin = *admin.key();
+        multi_signature_vault_info.creator = *admin.key();
+        multi_signature_vault_info.pending_admin = Pubkey::default();
         multi_signature_vault_info.is_active = true;
         multi_signature_vault_info.member_count = 0;
-        multi_signature_vault_info.member_keys = [Pubkey::default(); 10];
+        multi_signature_vault_info.member_slots = 0;
+        multi_signature_vault_info.total_weight = 0;
         multi_signature_vault_info.threshold = threshold;
+        multi_signature_vault_info.type_thresholds = [0; StreamType::MAX_TYPES];
+        multi_signature_vault_info.amount_tier_count = 0;
+        multi_signature_vault_info.amount_tiers = [AmountTier::default(); MultiSignatureVault::MAX_AMOUNT_TIERS];
         multi_signature_vault_info.proposal_expiry = proposal_expiry;
+        multi_signature_vault_info.timelock_delay = 0;
+        multi_signature_vault_info.guardian = Pubkey::default();
+        multi_signature_vault_info.pause_threshold = 0;
+        multi_signature_vault_info.deposit_allowlist = deposit_allowlist;
         multi_signature_vault_info.total_proposals = 0;
         multi_signature_vault_info.treasury_vault = *treasury_vault.key();
         multi_signature_vault_info.created_at = current_time;
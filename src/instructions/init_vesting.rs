@@ -2,69 +2,61 @@ use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::P
 use pinocchio_system::instructions::CreateAccount;
 use pinocchio_token::{instructions::InitializeAccount3, state::TokenAccount};
 
-use crate::states::VestingContractInfo;
+use crate::{error::TreasuryError, pda, states::VestingContractInfo};
 
 pub fn process_init_vesting_contract(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
-    let [creator, mint, vesting_contract_info, vault, comapny_token_account, system_program, token_program] = accounts else {
-        return Err(ProgramError::InvalidAccountData);
+    let [creator, mint, vesting_contract_info, vault, system_program, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     if !creator.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    if *token_program.key() != pinocchio_token::ID || *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
     };
 
     if *mint.owner() != pinocchio_token::ID {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidMint.into());
+    };
+
+    if instruction_data.len() < 32 {
+        return Err(ProgramError::InvalidInstructionData);
     };
 
     let mut company_name = [0u8; 32];
     company_name.copy_from_slice(&instruction_data[0..32]);
 
-    let (vesting_contract_info_pda, vesting_contract_bump) = pubkey::find_program_address(
-        &[company_name.as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
+    let (vesting_contract_info_pda, vesting_contract_bump) = pda::find_vesting_contract(&company_name, creator.key());
 
     if *vesting_contract_info.key() != vesting_contract_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let (vault_pda, vault_bump) = pubkey::find_program_address(
-        &[b"vault", mint.key().as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
+    let (vault_pda, vault_bump) = pda::find_vesting_vault(creator.key(), &company_name, mint.key());
 
     if *vault.key() != vault_pda {
-        return Err(ProgramError::InvalidAccountData);
-    };
-
-    let (company_token_account_pda, company_token_account_bump) = pubkey::find_program_address(
-        &[mint.key().as_ref(), creator.key().as_ref()],
-        &crate::ID
-    );
-
-    if *comapny_token_account.key() != company_token_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidVestingAccount.into());
     };
 
-    let company_token_account_info = TokenAccount::from_account_info(comapny_token_account)?;
-    if !company_token_account_info.is_initialized() {
-        return Err(ProgramError::InvalidAccountData);
+    if !vesting_contract_info.data_is_empty() {
+        msg!("Vesting contract already exists");
+        return Err(ProgramError::AccountAlreadyInitialized);
     };
 
-    let vault_account_info = TokenAccount::from_account_info(vault)?;
-
-    if !vault_account_info.is_initialized() {
+    if vault.data_is_empty() {
         msg!("Initializing Vault Account");
 
         let lamports = Rent::get()?.minimum_balance(TokenAccount::LEN);
 
         let vault_bump_ref = &[vault_bump];
         let vault_seeds = seeds!(
-            b"vault", 
-            mint.key().as_ref(), 
+            pda::VESTING_VAULT_SEED,
             creator.key().as_ref(),
+            company_name.as_ref(),
+            mint.key().as_ref(),
             vault_bump_ref
         );
         let vault_signer = Signer::from(&vault_seeds);
@@ -82,6 +74,12 @@ pub fn process_init_vesting_contract(accounts: &[AccountInfo], instruction_data:
             mint,
             owner: &vesting_contract_info_pda
         }.invoke()?;
+    } else {
+        let vault_account_info = TokenAccount::from_account_info(vault)?;
+
+        if *vault_account_info.owner() != vesting_contract_info_pda || vault_account_info.mint() != mint.key() {
+            return Err(TreasuryError::TokenAccountMismatch.into());
+        };
     };
 
     if vesting_contract_info.data_is_empty() {
@@ -105,7 +103,7 @@ pub fn process_init_vesting_contract(accounts: &[AccountInfo], instruction_data:
             owner: &crate::ID
         }.invoke_signed(&[vesting_signer])?;
 
-        let mut vesting_contract_info_mut = VestingContractInfo::from_account_info_mut(vesting_contract_info)?;
+        let mut vesting_contract_info_mut = VestingContractInfo::initialize(vesting_contract_info)?;
 
         vesting_contract_info_mut.creator = *creator.key();
         vesting_contract_info_mut.company_name = company_name;
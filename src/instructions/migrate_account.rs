@@ -0,0 +1,130 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, pda, instructions::execute_proposal::{mark_proposal_executed, verify_governed_proposal}, states::{AccountHeader, MultiSignatureVault, ProposalAction, StreamProposal, StreamType}};
+
+/// Upgrades `target`, the multisig account itself or one of its proposals, from the original
+/// layout to the current one at `authority`'s expense. The multisig account is migrated first
+/// and only by its admin. A proposal is migrated by the admin or, passing an approved
+/// `MigrateAccount` proposal naming `target` last, by its executor; once the admin role is
+/// renounced only the proposal path remains. Every check runs before the account is rewritten.
+pub fn process_migrate_account(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [authority, multisig_account, target, system_program, rest @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    if *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    if target.key() == multisig_account.key() {
+        let (admin, id) = MultiSignatureVault::original_seeds(target)?;
+
+        let (multisig_info_pda, _) = pda::find_multisig_info(&admin, id);
+
+        if *multisig_account.key() != multisig_info_pda {
+            return Err(TreasuryError::InvalidMultisigAccount.into());
+        };
+
+        // The original layout had no way to renounce the admin role or to govern a migration.
+        if *authority.key() != admin {
+            return Err(TreasuryError::NotAdmin.into());
+        };
+
+        MultiSignatureVault::migrate(target, authority)?;
+
+        log!("Multisig account migrated to layout version {}", MultiSignatureVault::VERSION);
+
+        return Ok(());
+    };
+
+    let (proposal_id, multisig_id) = StreamProposal::original_ids(target)?;
+
+    let multisig_info = MultiSignatureVault::from_account_info(multisig_account)?;
+
+    let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_info.creator, multisig_info.id);
+
+    if *multisig_account.key() != multisig_info_pda {
+        return Err(TreasuryError::InvalidMultisigAccount.into());
+    };
+
+    check_proposal_target(multisig_account.key(), multisig_info.id, target.key(), proposal_id, multisig_id)?;
+
+    let admin = multisig_info.admin;
+    drop(multisig_info);
+
+    match rest {
+        [] => {
+            if admin == Pubkey::default() || *authority.key() != admin {
+                msg!("Migration needs the admin or an approved MigrateAccount proposal");
+                return Err(TreasuryError::NotAdmin.into());
+            };
+        },
+        [stream_proposal_account, ..] => {
+            verify_governed_proposal(authority, stream_proposal_account, multisig_account, StreamType::MigrateAccount)?;
+
+            let ProposalAction::MigrateAccount(approved_target) = ProposalAction::try_from_payload(&StreamType::MigrateAccount, &StreamProposal::payload(stream_proposal_account)?)
+                .map_err(|_| TreasuryError::InvalidProposalPayload)? else {
+                return Err(TreasuryError::InvalidProposalPayload.into());
+            };
+
+            if approved_target != *target.key() {
+                msg!("Account does not match the approved migration");
+                return Err(TreasuryError::ProposalAccountMismatch.into());
+            };
+
+            let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+            let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+            mark_proposal_executed(&mut stream_proposal, &mut multisig_info, authority.key())?;
+        },
+    };
+
+    StreamProposal::migrate(target, authority)?;
+
+    log!("Proposal {} migrated to layout version {}", proposal_id, StreamProposal::VERSION);
+
+    Ok(())
+}
+
+/// Checks that the proposal at `target`, recording `proposal_id` and `multisig_id`, belongs to
+/// the multisig account at `multisig` with `id`. Proposals in the original layout still sit at
+/// their address from before pda namespacing, which only the ids seed.
+fn check_proposal_target(multisig: &Pubkey, id: u64, target: &Pubkey, proposal_id: u64, multisig_id: u64) -> ProgramResult {
+    if multisig_id != id {
+        return Err(TreasuryError::MultisigIdMismatch.into());
+    };
+
+    let (stream_proposal_pda, _) = pda::find_stream_proposal(multisig, proposal_id);
+    let (legacy_stream_proposal_pda, _) = pda::find_legacy_stream_proposal(proposal_id, multisig_id);
+
+    if *target != stream_proposal_pda && *target != legacy_stream_proposal_pda {
+        return Err(TreasuryError::InvalidProposalAccount.into());
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_proposal_target_must_belong_to_the_multisig() {
+        let multisig = [1u8; 32];
+        let foreign_multisig = [2u8; 32];
+        let target = pda::find_stream_proposal(&multisig, 3).0;
+
+        assert_eq!(check_proposal_target(&multisig, 7, &target, 3, 7), Ok(()));
+        assert_eq!(check_proposal_target(&foreign_multisig, 7, &target, 3, 7), Err(TreasuryError::InvalidProposalAccount.into()));
+        assert_eq!(check_proposal_target(&foreign_multisig, 8, &target, 3, 7), Err(TreasuryError::MultisigIdMismatch.into()));
+
+        let legacy_target = pda::find_legacy_stream_proposal(3, 7).0;
+        assert_eq!(check_proposal_target(&multisig, 7, &legacy_target, 3, 7), Ok(()));
+        assert_eq!(check_proposal_target(&multisig, 7, &legacy_target, 4, 7), Err(TreasuryError::InvalidProposalAccount.into()));
+    }
+}
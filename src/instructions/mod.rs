@@ -1,6 +1,8 @@
 use pinocchio::program_error::ProgramError;
 use shank::ShankInstruction;
 
+use crate::error::TreasuryError;
+
 pub mod init_multisig_vault;
 pub mod add_members;
 pub mod create_stream_proposal;
@@ -9,7 +11,6 @@ pub mod deposit_funds;
 pub mod init_batch_payments;
 pub mod batch_payments;
 pub mod token_transfers;
-pub mod init_stream_payments;
 pub mod withdraw_stream_payments;
 pub mod cancel_stream;
 pub mod init_vesting;
@@ -17,49 +18,355 @@ pub mod init_beneficiary;
 pub mod deposit_tokens_vesting;
 pub mod claim_vested_tokens;
 pub mod execute_proposal;
+pub mod treasury_actions;
+pub mod remove_member;
+pub mod update_config;
+pub mod migrate_account;
+pub mod update_member;
+pub mod cancel_proposal;
+pub mod change_vote;
+pub mod expire_proposal;
+pub mod close_proposal;
+pub mod emergency_pause;
+pub mod transfer_admin;
 
 #[repr(u8)]
-#[derive(ShankInstruction)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MultiSignatureInstructions {
+    InitMultisigVault = 0,
+    AddMember = 1,
+    CreateStreamProposal = 2,
+    VoteOnProposal = 3,
+    DepositTokens = 4,
+    ExecuteApprovedProposal = 5,
+    ClaimStreamPayment = 6,
+    ClaimVestedTokens = 7,
+    DepositFundsVault = 8,
+    CancelStream = 9,
+    RemoveMember = 10,
+    UpdateConfig = 11,
+    TransferTokens = 12,
+    InitializeBatchState = 13,
+    BatchTransfer = 14,
+    InitVestingContract = 15,
+    InitBeneficiary = 16,
+    MigrateAccount = 17,
+    UpdateMemberWeight = 18,
+    UpdateMemberRoles = 19,
+    CancelProposal = 20,
+    RevokeVote = 21,
+    ChangeVote = 22,
+    ExpireProposal = 23,
+    CloseProposal = 24,
+    EmergencyPause = 25,
+    Unpause = 26,
+    SetGuardian = 27,
+    TransferAdmin = 28,
+    AcceptAdmin = 29,
+    SetDepositAllowlist = 30,
+    AllowDepositor = 31,
+    CancelTreasuryStream = 32,
+}
+
+/// Account and argument layout of every instruction, used to generate the IDL. Args are
+/// little-endian and follow the discriminator byte. Dispatch goes through
+/// `MultiSignatureInstructions`; `tests/idl.rs` fails if the two or `idl/` drift apart.
+#[repr(u8)]
+#[derive(ShankInstruction)]
+pub enum MultiSignatureInstructionLayouts {
     #[account(0, writable, signer, name="admin", desc="Account that pays for account creation")]
     #[account(1, name="mint", desc="mint account")]
     #[account(2, writable, name="multisig_info", desc="multisig_info account")]
     #[account(3, writable, name="treasury_vault_account", desc="treasury vault account")]
     #[account(4, name="token_program", desc="TokenProgram")]
     #[account(5, name="system_program", desc="System program")]
-    InitMultisigVault = 0, 
-    
-    #[account(0, writable, signer, name="admin", desc="Account that pays for account creation")]
+    InitMultisigVault {
+        multisig_id: u64,
+        threshold: u64,
+        proposal_expiry: i64,
+        minimum_balance: u64,
+        deposit_allowlist: bool,
+    } = 0,
+
+    #[account(0, writable, signer, name="authority", desc="Vault admin, or an executor of an approved AddMember proposal; pays rent for the grown member list")]
     #[account(1, name="member", desc="member to add")]
     #[account(2, writable, name="multisig_info", desc="multisig_info account")]
     #[account(3, name="system_program", desc="System program")]
-    AddMember = 1, 
+    #[account(4, optional, writable, name="stream_proposal_account", desc="approved AddMember proposal for this member, weight and roles; required once the admin role is renounced")]
+    AddMember {
+        multisig_id: u64,
+        weight: u64,
+        roles: u8,
+    } = 1,
 
+    /// The action payload for `stream_type` (see `states::ProposalAction`) follows the args.
     #[account(0, writable, signer, name="proposer", desc="Account that pays for account creation")]
-    #[account(1, writable, name="stream_propsoal_account", desc="stream_propsoal_account")]
-    #[account(2, name = "multisig_account", desc = "multisig account that the proposal belongs to")]
+    #[account(1, writable, name="stream_propsoal_account", desc="proposal account at the multisig's next proposal ID, its `total_proposals`")]
+    #[account(2, writable, name = "multisig_account", desc = "multisig account that the proposal belongs to")]
     #[account(3, name="system_program", desc="System program")]
-    CreateStreamProposal = 2,
+    CreateStreamProposal {
+        multisig_id: u64,
+        stream_type: u8,
+        voting_deadline: i64,
+        stream_name: [u8; 32],
+        stream_description: [u8; 128],
+        allow_below_minimum: bool,
+    } = 2,
 
     #[account(0, writable, signer, name="voter", desc="person who is voting")]
     #[account(1, writable, name="stream_proposal_account", desc="stream_propsoal_account")]
     #[account(2, writable, name="multisig_info", desc="multisig_info account")]
     #[account(3, name="system_program", desc="System program")]
-    VoteOnProposal = 3,
+    VoteOnProposal {
+        proposal_id: u64,
+        multisig_id: u64,
+        vote_type: u64,
+    } = 3,
 
-    #[account(0, writable, signer, name="admin", desc="Account that pays for account creation")]
+    #[account(0, writable, signer, name="depositor", desc="anyone funding the treasury; pays for their receipt on a first deposit")]
     #[account(1, name="mint", desc="mint account")]
-    #[account(2, writable, name="source_token_account", desc="source_token_account")]
+    #[account(2, writable, name="source_token_account", desc="depositor's token account")]
     #[account(3, writable, name="treasury_vault_account", desc="treasury vault account")]
     #[account(4, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(5, writable, name="deposit_receipt", desc="depositor's receipt; must already be allowed while the deposit allowlist is on")]
+    #[account(6, name="token_program", desc="Token program")]
+    #[account(7, name="system_program", desc="System program")]
+    DepositTokens {
+        amount: u64,
+    } = 4,
+
+    /// The accounts required by the proposal's action follow `mint`:
+    /// TokenTransfers: recipient_token_account, token_program.
+    /// BatchPayments: token_program, then one token account per approved recipient.
+    /// PaymentStreaming: recipient, recipient_token_account, payment_stream_info, stream_escrow, system_program, token_program.
+    /// Vesting: beneficiary, vesting_contract_info, vault, vesting_record_info, beneficiary_token_account, system_program, token_program.
+    #[account(0, writable, signer, name="executor", desc="member with the executor role, who approved the proposal if they vote; pays rent for accounts the action creates")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(3, writable, name="treasury_vault_account", desc="treasury vault the payout is drawn from")]
+    #[account(4, name="mint", desc="mint of the approved payout")]
+    ExecuteApprovedProposal = 5,
+
+    #[account(0, signer, name="recipient", desc="stream recipient")]
+    #[account(1, writable, name="sender_token_account", desc="token account holding the streamed tokens")]
+    #[account(2, writable, name="recipient_token_account", desc="recipient token account")]
+    #[account(3, writable, name="payment_stream_info", desc="payment stream account")]
+    #[account(4, name="token_program", desc="Token program")]
+    #[account(5, optional, name="multisig_info", desc="sending multisig, required for treasury-funded streams; fails while paused")]
+    ClaimStreamPayment = 6,
+
+    #[account(0, signer, name="beneficiary", desc="vesting beneficiary")]
+    #[account(1, name="creator", desc="vesting contract creator; the multisig account for treasury-funded vesting, which fails while paused")]
+    #[account(2, name="mint", desc="mint account")]
+    #[account(3, writable, name="vesting_contract_info", desc="vesting contract account")]
+    #[account(4, writable, name="vesting_record_info", desc="beneficiary vesting record")]
+    #[account(5, writable, name="vault", desc="vesting vault")]
+    #[account(6, writable, name="beneficiary_token_account", desc="beneficiary token account")]
+    #[account(7, name="system_program", desc="System program")]
+    #[account(8, name="token_program", desc="Token program")]
+    ClaimVestedTokens {
+        company_name: [u8; 32],
+    } = 7,
+
+    #[account(0, signer, name="creator", desc="vesting contract creator")]
+    #[account(1, name="mint", desc="mint account")]
+    #[account(2, writable, name="vesting_contract_info", desc="vesting contract account")]
+    #[account(3, writable, name="vault", desc="vesting vault")]
+    #[account(4, writable, name="company_token_account", desc="creator token account the deposit is drawn from")]
+    #[account(5, name="system_program", desc="System program")]
+    #[account(6, name="token_program", desc="Token program")]
+    DepositFundsVault {
+        company_name: [u8; 32],
+        amount: u64,
+    } = 8,
+
+    #[account(0, signer, name="sender", desc="stream sender")]
+    #[account(1, name="recipient", desc="stream recipient")]
+    #[account(2, writable, name="sender_token_account", desc="token account holding the streamed tokens")]
+    #[account(3, writable, name="recipient_token_account", desc="recipient token account")]
+    #[account(4, writable, name="payment_stream_info", desc="payment stream account")]
     #[account(5, name="token_program", desc="Token program")]
     #[account(6, name="system_program", desc="System program")]
-    DepositTokens = 4,           
-    ExecuteApprovedProposal = 5, 
-    ClaimStreamPayment = 6,      
-    ClaimVestedTokens = 7,
-    DepositFundsVault = 8,
     CancelStream = 9,
+
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the removal proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved RemoveMember proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(3, optional, writable, name="active_proposals", desc="still-active proposals to strike the removed member's votes from")]
+    RemoveMember = 10,
+
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the config change proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved ConfigChange proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    UpdateConfig = 11,
+
+    // Standalone flows below are signed by the caller and never touch the multisig treasury;
+    // governed payouts go through ExecuteApprovedProposal.
+
+    #[account(0, writable, signer, name="sender", desc="owner of the source token account; pays rent for the recipient token account")]
+    #[account(1, name="recipient", desc="recipient wallet")]
+    #[account(2, name="mint", desc="mint account")]
+    #[account(3, writable, name="sender_token_account", desc="token account the transfer is drawn from")]
+    #[account(4, writable, name="recipient_token_account", desc="recipient token account, created if empty")]
+    #[account(5, name="token_program", desc="Token program")]
+    #[account(6, name="system_program", desc="System program")]
+    TransferTokens {
+        amount: u64,
+    } = 12,
+
+    #[account(0, writable, signer, name="creator", desc="batch creator; pays for account creation")]
+    #[account(1, name="mint", desc="mint account")]
+    #[account(2, writable, name="batch_state", desc="batch state account")]
+    #[account(3, writable, name="creator_token_account", desc="creator token account the batch is paid from, created if empty")]
+    #[account(4, name="system_program", desc="System program")]
+    #[account(5, name="token_program", desc="Token program")]
+    InitializeBatchState {
+        batch_id: u64,
+        total_recipients: u8,
+    } = 13,
+
+    /// `num_recipients` recipient wallets follow the args; their token accounts follow
+    /// `token_program` in the same order.
+    #[account(0, signer, name="creator", desc="batch creator")]
+    #[account(1, writable, name="creator_token_account", desc="creator token account the batch is paid from")]
+    #[account(2, name="mint", desc="mint account")]
+    #[account(3, writable, name="batch_state", desc="batch state account")]
+    #[account(4, name="token_program", desc="Token program")]
+    BatchTransfer {
+        batch_id: u64,
+        amount_per_recipient: u64,
+        num_recipients: u8,
+    } = 14,
+
+    #[account(0, writable, signer, name="creator", desc="vesting contract creator; pays for account creation")]
+    #[account(1, name="mint", desc="mint account")]
+    #[account(2, writable, name="vesting_contract_info", desc="vesting contract account")]
+    #[account(3, writable, name="vault", desc="vesting vault, created if empty")]
+    #[account(4, name="system_program", desc="System program")]
+    #[account(5, name="token_program", desc="Token program")]
+    InitVestingContract {
+        company_name: [u8; 32],
+    } = 15,
+
+    #[account(0, writable, signer, name="creator", desc="vesting contract creator; pays for account creation")]
+    #[account(1, name="beneficiary", desc="beneficiary wallet")]
+    #[account(2, name="mint", desc="mint account")]
+    #[account(3, writable, name="vesting_contract_info", desc="vesting contract account")]
+    #[account(4, writable, name="vesting_record_info", desc="beneficiary vesting record")]
+    #[account(5, writable, name="beneficiary_token_account", desc="beneficiary token account, created if empty")]
+    #[account(6, name="system_program", desc="System program")]
+    #[account(7, name="token_program", desc="Token program")]
+    InitBeneficiary {
+        company_name: [u8; 32],
+        cliff_period: i64,
+        start_time: i64,
+        end_time: i64,
+        amount: u64,
+    } = 16,
+
+    /// Upgrades an account from the original layout. Signed by the admin, or by an executor
+    /// passing an approved `MigrateAccount` proposal naming `target` as the last account.
+    #[account(0, writable, signer, name="authority", desc="pays for the larger layout; the admin, or an executor-role member when a migration proposal is passed")]
+    #[account(1, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(2, writable, name="target", desc="account to migrate: multisig_info itself or one of its proposals")]
+    #[account(3, name="system_program", desc="System program")]
+    #[account(4, optional, writable, name="stream_proposal_account", desc="approved MigrateAccount proposal")]
+    MigrateAccount = 17,
+
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the member weight proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved SetMemberWeight proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    UpdateMemberWeight = 18,
+
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the member roles proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved SetMemberRoles proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    UpdateMemberRoles = 19,
+
+    /// Consenting members sign as the accounts after `proposer` when the proposer cancels a
+    /// proposal that already has votes.
+    #[account(0, signer, name="authority", desc="admin or proposer")]
+    #[account(1, writable, name="stream_proposal_account", desc="proposal to cancel; closed")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(3, writable, name="proposer", desc="proposal creator, refunded the proposal rent")]
+    CancelProposal = 20,
+
+    #[account(0, signer, name="voter", desc="member withdrawing their vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="active or approved, unexecuted proposal")]
+    #[account(2, name="multisig_info", desc="multisig_info account")]
+    RevokeVote {
+        proposal_id: u64,
+        multisig_id: u64,
+    } = 21,
+
+    #[account(0, signer, name="voter", desc="member flipping their vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="active or approved, unexecuted proposal")]
+    #[account(2, name="multisig_info", desc="multisig_info account")]
+    ChangeVote {
+        proposal_id: u64,
+        multisig_id: u64,
+        vote_type: u64,
+    } = 22,
+
+    #[account(0, writable, name="stream_proposal_account", desc="proposal past its voting deadline; closed")]
+    #[account(1, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(2, writable, name="proposer", desc="proposal creator, refunded the proposal rent")]
+    ExpireProposal = 23,
+
+    /// Emits the proposal's outcome as a `ProposalRecord` program data log before closing it.
+    #[account(0, writable, name="stream_proposal_account", desc="executed, rejected, expired or cancelled proposal; closed")]
+    #[account(1, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(2, writable, name="proposer", desc="proposal creator, refunded the proposal rent")]
+    CloseProposal = 24,
+
+    /// Members consenting to the pause sign as the accounts after `multisig_info` unless the
+    /// authority is the guardian.
+    #[account(0, signer, name="authority", desc="guardian, or a member signing with others who together hold the pause threshold")]
+    #[account(1, writable, name="multisig_info", desc="multisig_info account")]
+    EmergencyPause = 25,
+
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the unpause proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="Unpause proposal approved by the full voting weight")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    Unpause = 26,
+
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the guardian proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved SetGuardian proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    SetGuardian = 27,
+
+    /// Naming the default key renounces the admin role; otherwise the new admin must accept.
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the admin proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved TransferAdmin proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    TransferAdmin = 28,
+
+    #[account(0, signer, name="new_admin", desc="pending admin named by an executed TransferAdmin proposal")]
+    #[account(1, writable, name="multisig_info", desc="multisig_info account")]
+    AcceptAdmin = 29,
+
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the allowlist proposal if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved SetDepositAllowlist proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    SetDepositAllowlist = 30,
+
+    #[account(0, writable, signer, name="executor", desc="member with the executor role; pays for the receipt of a depositor who has never deposited")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved AllowDepositor proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account")]
+    #[account(3, writable, name="deposit_receipt", desc="receipt of the depositor named by the proposal")]
+    #[account(4, name="system_program", desc="System program")]
+    AllowDepositor = 31,
+
+    /// Pays the recipient what has streamed so far and refunds the rest of the escrow to the treasury.
+    #[account(0, signer, name="executor", desc="member with the executor role, who approved the cancellation if they vote")]
+    #[account(1, writable, name="stream_proposal_account", desc="approved CancelStream proposal")]
+    #[account(2, writable, name="multisig_info", desc="multisig_info account, sender of the stream")]
+    #[account(3, writable, name="treasury_vault", desc="treasury vault refunded the unstreamed tokens")]
+    #[account(4, writable, name="stream_escrow", desc="escrow holding the unstreamed balance")]
+    #[account(5, writable, name="recipient_token_account", desc="token account of the stream recipient")]
+    #[account(6, writable, name="payment_stream_info", desc="payment stream to the recipient named by the proposal")]
+    #[account(7, name="token_program", desc="Token program")]
+    CancelTreasuryStream = 32,
 }
 
 impl TryFrom<&u8> for MultiSignatureInstructions {
@@ -77,7 +384,30 @@ impl TryFrom<&u8> for MultiSignatureInstructions {
             7 => Ok(MultiSignatureInstructions::ClaimVestedTokens),
             8 => Ok(MultiSignatureInstructions::DepositFundsVault),
             9 => Ok(MultiSignatureInstructions::CancelStream),
-            _ => Err(ProgramError::InvalidInstructionData)
+            10 => Ok(MultiSignatureInstructions::RemoveMember),
+            11 => Ok(MultiSignatureInstructions::UpdateConfig),
+            12 => Ok(MultiSignatureInstructions::TransferTokens),
+            13 => Ok(MultiSignatureInstructions::InitializeBatchState),
+            14 => Ok(MultiSignatureInstructions::BatchTransfer),
+            15 => Ok(MultiSignatureInstructions::InitVestingContract),
+            16 => Ok(MultiSignatureInstructions::InitBeneficiary),
+            17 => Ok(MultiSignatureInstructions::MigrateAccount),
+            18 => Ok(MultiSignatureInstructions::UpdateMemberWeight),
+            19 => Ok(MultiSignatureInstructions::UpdateMemberRoles),
+            20 => Ok(MultiSignatureInstructions::CancelProposal),
+            21 => Ok(MultiSignatureInstructions::RevokeVote),
+            22 => Ok(MultiSignatureInstructions::ChangeVote),
+            23 => Ok(MultiSignatureInstructions::ExpireProposal),
+            24 => Ok(MultiSignatureInstructions::CloseProposal),
+            25 => Ok(MultiSignatureInstructions::EmergencyPause),
+            26 => Ok(MultiSignatureInstructions::Unpause),
+            27 => Ok(MultiSignatureInstructions::SetGuardian),
+            28 => Ok(MultiSignatureInstructions::TransferAdmin),
+            29 => Ok(MultiSignatureInstructions::AcceptAdmin),
+            30 => Ok(MultiSignatureInstructions::SetDepositAllowlist),
+            31 => Ok(MultiSignatureInstructions::AllowDepositor),
+            32 => Ok(MultiSignatureInstructions::CancelTreasuryStream),
+            _ => Err(TreasuryError::UnknownInstruction.into())
         }
     }
 }
\ No newline at end of file
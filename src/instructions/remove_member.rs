@@ -0,0 +1,86 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, pda, instructions::execute_proposal::{mark_proposal_executed, verify_governed_proposal}, states::{Member, MultiSignatureVault, ProposalAction, ProposalStatus, StreamProposal, StreamType}};
+
+pub fn process_remove_member(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account, active_proposals @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::RemoveMember)?;
+
+    let ProposalAction::RemoveMember(member) = ProposalAction::try_from_payload(&StreamType::RemoveMember, &StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)? else {
+        return Err(TreasuryError::InvalidProposalPayload.into());
+    };
+
+    let member_slot = MultiSignatureVault::with_members_mut(multisig_account, |multisig_info, members| {
+        let Some(slot) = MultiSignatureVault::member_slot(members, &member) else {
+            log!("Member to remove is no longer part of the multisig");
+            return Err(TreasuryError::MemberNotFound.into());
+        };
+
+        let remaining_weight = multisig_info.total_weight.saturating_sub(members[slot].voting_weight());
+        if multisig_info.max_threshold() > remaining_weight {
+            log!("Cannot remove member: threshold {} would exceed remaining weight {}", multisig_info.max_threshold(), remaining_weight);
+            return Err(TreasuryError::InvalidThreshold.into());
+        };
+
+        if !MultiSignatureVault::keeps_role_holders(members, slot, &Member::new(Pubkey::default(), 0, 0)) {
+            log!("Cannot remove member: no member would be left to propose or execute");
+            return Err(TreasuryError::MissingRoleHolder.into());
+        };
+
+        multisig_info.remove_member(members, &member)
+    })?;
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    let multisig_id = multisig_info.id;
+    let member_count = multisig_info.member_count;
+    drop(stream_proposal);
+    drop(multisig_info);
+
+    let members = MultiSignatureVault::members(multisig_account)?;
+
+    for proposal_account in active_proposals {
+        if proposal_account.key() == stream_proposal_account.key() {
+            continue;
+        }
+
+        if *proposal_account.owner() != crate::ID {
+            return Err(ProgramError::InvalidAccountOwner);
+        };
+
+        StreamProposal::with_votes_mut(proposal_account, |proposal, votes| {
+            let (proposal_pda, _) = pda::find_stream_proposal(multisig_account.key(), proposal.proposal_id);
+
+            if *proposal_account.key() != proposal_pda {
+                return Err(TreasuryError::InvalidProposalAccount.into());
+            };
+
+            if proposal.multisig_id != multisig_id {
+                return Err(TreasuryError::MultisigIdMismatch.into());
+            };
+
+            let status = ProposalStatus::try_from(&proposal.status)?;
+            if status != ProposalStatus::Active && status != ProposalStatus::Approved {
+                return Ok(());
+            }
+
+            if proposal.remove_vote(votes, &members, member_slot) {
+                log!("Removed member vote from proposal {}", proposal.proposal_id);
+            }
+
+            Ok(())
+        })?;
+    }
+
+    log!("Member removed successfully! Total members: {}", member_count);
+
+    Ok(())
+}
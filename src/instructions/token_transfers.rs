@@ -3,6 +3,8 @@ use pinocchio_system::instructions::CreateAccount;
 use pinocchio_token::{instructions::TransferChecked, state::{Mint, TokenAccount}};
 use pinocchio_token::instructions::InitializeAccount3;
 
+use crate::{error::TreasuryError, pda};
+
 pub fn process_transfer_tokens(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
     let [sender, recepient, mint, sender_token_account, recepient_token_account, token_program, system_program] = accounts else {
@@ -10,54 +12,63 @@ pub fn process_transfer_tokens(accounts: &[AccountInfo], instruction_data: &[u8]
     };
 
     if !sender.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    if *token_program.key() != pinocchio_token::ID || *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
     };
 
     if *mint.owner() != pinocchio_token::ID {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidMint.into());
+    };
+
+    if instruction_data.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
     };
 
     let amount_to_transfer = u64::from_le_bytes(
         instruction_data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?
     );
 
+    if amount_to_transfer == 0 {
+        return Err(TreasuryError::InvalidAmount.into());
+    };
+
     let sender_token_account_info = TokenAccount::from_account_info(sender_token_account)?;
 
     if !sender_token_account_info.is_initialized() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::UninitializedAccount);
     };
 
     if sender_token_account_info.owner() != sender.key() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::TokenAccountMismatch.into());
     };
 
     if sender_token_account_info.mint() != mint.key() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::TokenAccountMismatch.into());
     };
 
     if sender_token_account_info.amount() < amount_to_transfer {
         return Err(ProgramError::InsufficientFunds);
     };
 
-    let (recepient_token_account_pda, bump) = pubkey::find_program_address(
-        &[b"recepient_token_account", recepient.key().as_ref(), mint.key().as_ref()],
-        &crate::ID
-    );
+    drop(sender_token_account_info);
+
+    let (recepient_token_account_pda, bump) = pda::find_recipient_token_account(recepient.key(), mint.key());
 
     if *recepient_token_account.key() != recepient_token_account_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::TokenAccountMismatch.into());
     };
 
-    let recepient_token_account_info = TokenAccount::from_account_info(recepient_token_account)?;
-
-    if !recepient_token_account_info.is_initialized() {
+    if recepient_token_account.data_is_empty() {
         msg!("Initializing Recipient Token Account");
 
         let lamports = Rent::get()?.minimum_balance(TokenAccount::LEN);
 
         let bump_ref = &[bump];
         let seeds = seeds!(
-            b"recepient_token_account",
+            pda::RECIPIENT_TOKEN_ACCOUNT_SEED,
             recepient.key().as_ref(), 
             mint.key().as_ref(),
             bump_ref
@@ -77,6 +88,12 @@ pub fn process_transfer_tokens(accounts: &[AccountInfo], instruction_data: &[u8]
             mint,
             owner: recepient.key(),
         }.invoke()?;
+    } else {
+        let recepient_token_account_info = TokenAccount::from_account_info(recepient_token_account)?;
+
+        if recepient_token_account_info.owner() != recepient.key() || recepient_token_account_info.mint() != mint.key() {
+            return Err(TreasuryError::TokenAccountMismatch.into());
+        };
     };
 
     let mint_info = Mint::from_account_info(mint)?;
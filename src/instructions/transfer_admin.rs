@@ -0,0 +1,76 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, Sysvar}, *};
+
+use crate::{error::TreasuryError, pda, instructions::execute_proposal::{mark_proposal_executed, verify_governed_proposal}, states::{MultiSignatureVault, ProposalAction, StreamProposal, StreamType}};
+
+/// Executes an approved `TransferAdmin` proposal. The new admin takes over once they accept;
+/// a proposal naming the default key renounces the admin role at once.
+pub fn process_transfer_admin(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::TransferAdmin)?;
+
+    let ProposalAction::TransferAdmin(new_admin) = ProposalAction::try_from_payload(&StreamType::TransferAdmin, &StreamProposal::payload(stream_proposal_account)?)? else {
+        return Err(TreasuryError::InvalidProposalPayload.into());
+    };
+
+    // Members may have changed since the proposal was created.
+    if MultiSignatureVault::is_member(&MultiSignatureVault::members(multisig_account)?, &new_admin) {
+        return Err(TreasuryError::AdminCannotBeMember.into());
+    };
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+
+    if new_admin == Pubkey::default() {
+        multisig_info.admin = Pubkey::default();
+        multisig_info.pending_admin = Pubkey::default();
+        msg!("Admin role renounced!");
+    } else {
+        multisig_info.pending_admin = new_admin;
+        msg!("Admin transfer pending acceptance");
+    };
+
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    Ok(())
+}
+
+/// Completes an admin transfer; signed by the pending admin.
+pub fn process_accept_admin(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [new_admin, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !new_admin.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    };
+
+    // The pending admin may have been added as a member since the transfer was executed.
+    if MultiSignatureVault::is_member(&MultiSignatureVault::members(multisig_account)?, new_admin.key()) {
+        return Err(TreasuryError::AdminCannotBeMember.into());
+    };
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+
+    let (multisig_info_pda, _) = pda::find_multisig_info(&multisig_info.creator, multisig_info.id);
+
+    if *multisig_account.key() != multisig_info_pda {
+        return Err(TreasuryError::InvalidMultisigAccount.into());
+    };
+
+    if multisig_info.pending_admin == Pubkey::default() || *new_admin.key() != multisig_info.pending_admin {
+        return Err(TreasuryError::NotPendingAdmin.into());
+    };
+
+    multisig_info.admin = *new_admin.key();
+    multisig_info.pending_admin = Pubkey::default();
+    multisig_info.last_updated = Clock::get()?.unix_timestamp;
+
+    msg!("Admin transfer accepted!");
+
+    Ok(())
+}
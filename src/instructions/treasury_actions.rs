@@ -0,0 +1,465 @@
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{instructions::{InitializeAccount3, TransferChecked}, state::{Mint, TokenAccount}};
+
+use crate::{error::TreasuryError, pda, states::{BatchPaymentPayload, MultiSignatureVault, PaymentStreamingInfo, StreamPaymentPayload, TokenTransferPayload, VestingContractInfo, VestingPayload, VestingRecordInfo}};
+
+// Payouts for approved proposals. Funds always leave `treasury_vault`, signed by the
+// `multisig_info` PDA that owns it; the executor only pays rent for any accounts created.
+
+/// The multisig treasury vault, validated against the `multisig_info` PDA that owns it.
+pub struct Treasury<'a> {
+    pub multisig_account: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    /// Set by approved emergency proposals to spend below `minimum_balance`.
+    pub allow_below_minimum: bool,
+}
+
+impl<'a> Treasury<'a> {
+    pub fn load(
+        multisig_account: &'a AccountInfo,
+        vault: &'a AccountInfo,
+        mint: &'a AccountInfo,
+        allow_below_minimum: bool,
+    ) -> Result<Self, ProgramError> {
+        let multisig_info = MultiSignatureVault::from_account_info(multisig_account)?;
+
+        if *vault.key() != multisig_info.treasury_vault {
+            msg!("Treasury vault does not belong to this multisig");
+            return Err(TreasuryError::InvalidTreasuryVault.into());
+        };
+
+        let treasury_vault_info = TokenAccount::from_account_info(vault)?;
+
+        if treasury_vault_info.owner() != multisig_account.key() || treasury_vault_info.mint() != mint.key() {
+            return Err(TreasuryError::InvalidTreasuryVault.into());
+        };
+
+        Ok(Self { multisig_account, vault, mint, allow_below_minimum })
+    }
+
+    /// Fails unless the treasury still holds `minimum_balance` after paying out `amount`.
+    pub fn check_minimum_balance(&self, amount: u64) -> ProgramResult {
+        if self.allow_below_minimum {
+            return Ok(());
+        }
+
+        let balance = TokenAccount::from_account_info(self.vault)?.amount();
+
+        if !MultiSignatureVault::from_account_info(self.multisig_account)?.keeps_minimum_balance(balance, amount) {
+            msg!("Payout would take the treasury below its minimum balance");
+            return Err(TreasuryError::MinimumBalanceBreached.into());
+        };
+
+        Ok(())
+    }
+
+    pub fn transfer(&self, to: &AccountInfo, amount: u64) -> ProgramResult {
+        self.check_minimum_balance(amount)?;
+
+        let multisig_info = MultiSignatureVault::from_account_info(self.multisig_account)?;
+        let creator = multisig_info.creator;
+        let multisig_id_ref = multisig_info.id.to_le_bytes();
+        let bump_ref = &[multisig_info.bump];
+        drop(multisig_info);
+
+        let seeds = seeds!(
+            pda::MULTISIG_INFO_SEED,
+            creator.as_ref(),
+            multisig_id_ref.as_ref(),
+            bump_ref
+        );
+        let signer_seeds = Signer::from(&seeds);
+
+        let decimals = Mint::from_account_info(self.mint)?.decimals();
+
+        TransferChecked {
+            from: self.vault,
+            mint: self.mint,
+            to,
+            authority: self.multisig_account,
+            amount,
+            decimals,
+        }.invoke_signed(&[signer_seeds])
+    }
+}
+
+fn validate_recipient_token_account(token_account: &AccountInfo, recipient: &[u8; 32], mint: &AccountInfo) -> ProgramResult {
+    let token_account_info = TokenAccount::from_account_info(token_account)?;
+
+    if token_account_info.owner() != recipient || token_account_info.mint() != mint.key() {
+        msg!("Recipient token account does not match the approved proposal");
+        return Err(TreasuryError::TokenAccountMismatch.into());
+    };
+
+    Ok(())
+}
+
+pub fn execute_token_transfer(
+    treasury: &Treasury,
+    payload: &TokenTransferPayload,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let [recipient_token_account, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if *token_program.key() != pinocchio_token::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    validate_recipient_token_account(recipient_token_account, &payload.recipient, treasury.mint)?;
+
+    treasury.transfer(recipient_token_account, payload.amount)
+}
+
+pub fn execute_batch_payments(
+    treasury: &Treasury,
+    payload: &BatchPaymentPayload,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let [token_program, recipient_token_accounts @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if *token_program.key() != pinocchio_token::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    let recipients = payload.get_active_recipients();
+    if recipient_token_accounts.len() != recipients.len() {
+        msg!("Recipient accounts do not match the approved batch");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    for (recipient, recipient_token_account) in recipients.iter().zip(recipient_token_accounts) {
+        validate_recipient_token_account(recipient_token_account, recipient, treasury.mint)?;
+
+        treasury.transfer(recipient_token_account, payload.amount_per_recipient)?;
+    }
+
+    Ok(())
+}
+
+/// Creates the payment stream with the multisig as sender. The streamed tokens are moved
+/// from the treasury into an escrow owned by the stream PDA, which signs withdrawals.
+pub fn execute_stream_payment(
+    executor: &AccountInfo,
+    treasury: &Treasury,
+    payload: &StreamPaymentPayload,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let [recipient, recipient_token_account, payment_stream_info, stream_escrow, system_program, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (multisig_account, mint) = (treasury.multisig_account, treasury.mint);
+
+    if *recipient.key() != payload.recipient {
+        msg!("Recipient does not match the approved stream");
+        return Err(TreasuryError::ProposalAccountMismatch.into());
+    };
+
+    if *token_program.key() != pinocchio_token::ID || *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    validate_recipient_token_account(recipient_token_account, recipient.key(), mint)?;
+
+    let duration_hours = (payload.end_time - payload.start_time) / 3600;
+    if duration_hours <= 0 {
+        msg!("Duration must be at least 1 hour");
+        return Err(TreasuryError::InvalidSchedule.into());
+    };
+
+    let (payment_stream_info_pda, payment_stream_info_bump) = pda::find_payment_stream(multisig_account.key(), recipient.key());
+
+    if *payment_stream_info.key() != payment_stream_info_pda {
+        return Err(TreasuryError::InvalidStreamAccount.into());
+    };
+
+    let (stream_escrow_pda, stream_escrow_bump) = pda::find_stream_escrow(payment_stream_info.key());
+
+    if *stream_escrow.key() != stream_escrow_pda {
+        return Err(TreasuryError::InvalidStreamAccount.into());
+    };
+
+    if !payment_stream_info.data_is_empty() || !stream_escrow.data_is_empty() {
+        msg!("Payment Stream Account Info Already Exists");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    };
+
+    let rent = Rent::get()?;
+
+    let bump = &[stream_escrow_bump];
+    let seeds = seeds!(
+        pda::STREAM_ESCROW_SEED,
+        payment_stream_info.key().as_ref(),
+        bump
+    );
+    let signer_seeds = Signer::from(&seeds);
+
+    CreateAccount {
+        from: executor,
+        to: stream_escrow,
+        lamports: rent.minimum_balance(TokenAccount::LEN),
+        space: TokenAccount::LEN as u64,
+        owner: &pinocchio_token::ID,
+    }.invoke_signed(&[signer_seeds])?;
+
+    InitializeAccount3 {
+        account: stream_escrow,
+        mint,
+        owner: payment_stream_info.key(),
+    }.invoke()?;
+
+    let bump = &[payment_stream_info_bump];
+    let seeds = seeds!(
+        pda::PAYMENT_STREAM_SEED,
+        multisig_account.key().as_ref(),
+        recipient.key().as_ref(),
+        bump
+    );
+    let signer_seeds = Signer::from(&seeds);
+
+    CreateAccount {
+        from: executor,
+        to: payment_stream_info,
+        lamports: rent.minimum_balance(PaymentStreamingInfo::SIZE),
+        space: PaymentStreamingInfo::SIZE as u64,
+        owner: &crate::ID
+    }.invoke_signed(&[signer_seeds])?;
+
+    {
+        let mut payment_stream_info_account = PaymentStreamingInfo::initialize(payment_stream_info)?;
+        payment_stream_info_account.sender = *multisig_account.key();
+        payment_stream_info_account.recipient = *recipient.key();
+        payment_stream_info_account.sender_token_account = *stream_escrow.key();
+        payment_stream_info_account.recipient_token_account = *recipient_token_account.key();
+        payment_stream_info_account.total_amount = payload.total_amount;
+        payment_stream_info_account.amount_per_hour = payload.total_amount / (duration_hours as u64);
+        payment_stream_info_account.start_time = payload.start_time;
+        payment_stream_info_account.end_time = payload.end_time;
+        payment_stream_info_account.withdrawn_amount = 0;
+        payment_stream_info_account.is_active = true;
+    }
+
+    treasury.transfer(stream_escrow, payload.total_amount)?;
+
+    msg!("Treasury-funded payment stream initialized!");
+
+    Ok(())
+}
+
+/// Sets up (or tops up) the vesting contract owned by the multisig, registers the
+/// beneficiary and funds the vesting vault from the treasury.
+pub fn execute_vesting(
+    executor: &AccountInfo,
+    treasury: &Treasury,
+    payload: &VestingPayload,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let [beneficiary, vesting_contract_info, vault, vesting_record_info, beneficiary_token_account, system_program, token_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (multisig_account, mint) = (treasury.multisig_account, treasury.mint);
+
+    if *beneficiary.key() != payload.beneficiary {
+        msg!("Beneficiary does not match the approved vesting schedule");
+        return Err(TreasuryError::ProposalAccountMismatch.into());
+    };
+
+    if *token_program.key() != pinocchio_token::ID || *system_program.key() != pinocchio_system::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    };
+
+    let creator_key = *multisig_account.key();
+    let company_name = payload.company_name;
+
+    let (vesting_contract_info_pda, vesting_contract_bump) = pda::find_vesting_contract(&company_name, &creator_key);
+
+    if *vesting_contract_info.key() != vesting_contract_info_pda {
+        return Err(TreasuryError::InvalidVestingAccount.into());
+    };
+
+    let (vault_pda, vault_bump) = pda::find_vesting_vault(&creator_key, &company_name, mint.key());
+
+    // Topping up a contract created before vaults were seeded per contract keeps its original vault.
+    let expected_vault = if vesting_contract_info.data_is_empty() {
+        vault_pda
+    } else {
+        VestingContractInfo::from_account_info(vesting_contract_info)?.vault_account
+    };
+
+    if *vault.key() != expected_vault {
+        return Err(TreasuryError::InvalidVestingAccount.into());
+    };
+
+    let (vesting_record_info_pda, vesting_record_info_bump) = pda::find_vesting_record(vesting_contract_info.key(), beneficiary.key());
+
+    if *vesting_record_info.key() != vesting_record_info_pda {
+        return Err(TreasuryError::InvalidVestingAccount.into());
+    };
+
+    let (beneficiary_token_account_pda, beneficiary_token_account_bump) = pda::find_beneficiary_token_account(beneficiary.key(), mint.key());
+
+    if *beneficiary_token_account.key() != beneficiary_token_account_pda {
+        return Err(TreasuryError::InvalidVestingAccount.into());
+    };
+
+    if !vesting_record_info.data_is_empty() {
+        msg!("Beneficiary already has a vesting record");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    };
+
+    let rent = Rent::get()?;
+
+    if vault.data_is_empty() {
+        msg!("Initializing Vault Account");
+
+        let bump = &[vault_bump];
+        let seeds = seeds!(
+            pda::VESTING_VAULT_SEED,
+            creator_key.as_ref(),
+            company_name.as_ref(),
+            mint.key().as_ref(),
+            bump
+        );
+        let signer_seeds = Signer::from(&seeds);
+
+        CreateAccount {
+            from: executor,
+            to: vault,
+            lamports: rent.minimum_balance(TokenAccount::LEN),
+            space: TokenAccount::LEN as u64,
+            owner: &pinocchio_token::ID
+        }.invoke_signed(&[signer_seeds])?;
+
+        InitializeAccount3 {
+            account: vault,
+            mint,
+            owner: &vesting_contract_info_pda
+        }.invoke()?;
+    };
+
+    if vesting_contract_info.data_is_empty() {
+        msg!("Creating Vesting Contract Info Account");
+
+        let bump = &[vesting_contract_bump];
+        let seeds = seeds!(
+            company_name.as_ref(),
+            creator_key.as_ref(),
+            bump
+        );
+        let signer_seeds = Signer::from(&seeds);
+
+        CreateAccount {
+            from: executor,
+            to: vesting_contract_info,
+            lamports: rent.minimum_balance(VestingContractInfo::SIZE),
+            space: VestingContractInfo::SIZE as u64,
+            owner: &crate::ID
+        }.invoke_signed(&[signer_seeds])?;
+
+        let mut vesting_contract_info_mut = VestingContractInfo::initialize(vesting_contract_info)?;
+        vesting_contract_info_mut.creator = creator_key;
+        vesting_contract_info_mut.company_name = company_name;
+        vesting_contract_info_mut.mint = *mint.key();
+        vesting_contract_info_mut.total_vested_tokens = 0;
+        vesting_contract_info_mut.total_available_tokens = 0;
+        vesting_contract_info_mut.total_locked_tokens = 0;
+        vesting_contract_info_mut.total_claimed_tokens = 0;
+        vesting_contract_info_mut.vault_account = *vault.key();
+        vesting_contract_info_mut.created_at = Clock::get()?.unix_timestamp;
+        vesting_contract_info_mut.vault_bump = vault_bump;
+        vesting_contract_info_mut.bump = vesting_contract_bump;
+        vesting_contract_info_mut.is_active = true;
+        vesting_contract_info_mut.fully_claimed = false;
+    };
+
+    if beneficiary_token_account.data_is_empty() {
+        msg!("Initializing Beneficiary Token Account");
+
+        let bump = &[beneficiary_token_account_bump];
+        let seeds = seeds!(
+            beneficiary.key().as_ref(),
+            mint.key().as_ref(),
+            bump
+        );
+        let signer_seeds = Signer::from(&seeds);
+
+        CreateAccount {
+            from: executor,
+            to: beneficiary_token_account,
+            lamports: rent.minimum_balance(TokenAccount::LEN),
+            space: TokenAccount::LEN as u64,
+            owner: &pinocchio_token::ID
+        }.invoke_signed(&[signer_seeds])?;
+
+        InitializeAccount3 {
+            account: beneficiary_token_account,
+            mint,
+            owner: beneficiary.key(),
+        }.invoke()?;
+    };
+
+    let bump = &[vesting_record_info_bump];
+    let seeds = seeds!(
+        pda::VESTING_RECORD_SEED,
+        vesting_contract_info.key().as_ref(),
+        beneficiary.key().as_ref(),
+        bump
+    );
+    let signer_seeds = Signer::from(&seeds);
+
+    CreateAccount {
+        from: executor,
+        to: vesting_record_info,
+        lamports: rent.minimum_balance(VestingRecordInfo::SIZE),
+        space: VestingRecordInfo::SIZE as u64,
+        owner: &crate::ID
+    }.invoke_signed(&[signer_seeds])?;
+
+    {
+        let mut vesting_contract_info_mut = VestingContractInfo::from_account_info_mut(vesting_contract_info)?;
+
+        if vesting_contract_info_mut.creator != creator_key || vesting_contract_info_mut.mint != *mint.key() {
+            return Err(TreasuryError::InvalidVestingAccount.into());
+        };
+
+        if !vesting_contract_info_mut.is_active {
+            msg!("Vesting contract is not active");
+            return Err(TreasuryError::VestingInactive.into());
+        };
+
+        let mut vesting_record_info_mut = VestingRecordInfo::initialize(vesting_record_info)?;
+        vesting_record_info_mut.beneficiary = *beneficiary.key();
+        vesting_record_info_mut.mint = *mint.key();
+        vesting_record_info_mut.total_vested_tokens = payload.amount;
+        vesting_record_info_mut.total_claimed_tokens_by_beneficiary = 0;
+        vesting_record_info_mut.cliff_period = payload.cliff_period;
+        vesting_record_info_mut.vault_bump = vesting_contract_info_mut.vault_bump;
+        vesting_record_info_mut.vesting_contract_info_bump = vesting_contract_info_mut.bump;
+        vesting_record_info_mut.bump = vesting_record_info_bump;
+        vesting_record_info_mut.beneficiary_ata = *beneficiary_token_account.key();
+        vesting_record_info_mut.has_claimed = false;
+        vesting_record_info_mut.start_time = payload.start_time;
+        vesting_record_info_mut.end_time = payload.end_time;
+
+        vesting_contract_info_mut.total_vested_tokens = vesting_contract_info_mut.total_vested_tokens
+            .checked_add(payload.amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        vesting_contract_info_mut.total_locked_tokens = vesting_contract_info_mut.total_locked_tokens
+            .checked_add(payload.amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        vesting_contract_info_mut.fully_claimed = false;
+    }
+
+    treasury.transfer(vault, payload.amount)?;
+
+    msg!("Treasury-funded vesting schedule created!");
+
+    Ok(())
+}
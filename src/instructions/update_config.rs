@@ -0,0 +1,42 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, instructions::execute_proposal::{mark_proposal_executed, verify_governed_proposal}, states::{ConfigChangePayload, MultiSignatureVault, StreamProposal, StreamType}};
+
+pub fn process_update_config(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::ConfigChange)?;
+
+    let config = ConfigChangePayload::try_from_bytes(&StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)?;
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+
+    // Membership or member weights may have changed since the proposal was created.
+    if config.validate(multisig_info.total_weight).is_err() {
+        log!("Cannot apply config: threshold {} is invalid for total weight {}", config.threshold, multisig_info.total_weight);
+        return Err(TreasuryError::InvalidConfig.into());
+    };
+
+    multisig_info.threshold = config.threshold;
+    for entry in config.get_type_thresholds() {
+        multisig_info.type_thresholds[entry.stream_type as usize] = entry.threshold;
+    }
+    if config.replaces_amount_tiers {
+        multisig_info.amount_tier_count = config.num_amount_tiers as u64;
+        multisig_info.amount_tiers = config.amount_tiers;
+    }
+    multisig_info.proposal_expiry = config.proposal_expiry;
+    multisig_info.timelock_delay = config.timelock_delay;
+    multisig_info.minimum_balance = config.minimum_balance;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    log!("Vault config updated! Threshold: {}, proposal expiry: {}, timelock: {}", multisig_info.threshold, multisig_info.proposal_expiry, multisig_info.timelock_delay);
+
+    Ok(())
+}
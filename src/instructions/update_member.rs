@@ -0,0 +1,84 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, *};
+use pinocchio_log::log;
+
+use crate::{error::TreasuryError, instructions::execute_proposal::{mark_proposal_executed, verify_governed_proposal}, states::{Member, MemberRolesPayload, MemberWeightPayload, MultiSignatureVault, StreamProposal, StreamType}};
+
+pub fn process_update_member_weight(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::SetMemberWeight)?;
+
+    let payload = MemberWeightPayload::try_from_bytes(&StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)?;
+
+    apply_member_update(executor, stream_proposal_account, multisig_account, &payload.member, |member| {
+        Member::new(member.key, payload.weight, member.roles)
+    })?;
+
+    log!("Member weight set to {}!", payload.weight);
+
+    Ok(())
+}
+
+pub fn process_update_member_roles(accounts: &[AccountInfo]) -> ProgramResult {
+
+    let [executor, stream_proposal_account, multisig_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    verify_governed_proposal(executor, stream_proposal_account, multisig_account, StreamType::SetMemberRoles)?;
+
+    let payload = MemberRolesPayload::try_from_bytes(&StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)?;
+
+    apply_member_update(executor, stream_proposal_account, multisig_account, &payload.member, |member| {
+        Member::new(member.key, member.weight, payload.roles)
+    })?;
+
+    log!("Member roles set to {}!", payload.roles);
+
+    Ok(())
+}
+
+/// Replaces the entry of `key` with `update` of it and marks the proposal executed.
+fn apply_member_update(
+    executor: &AccountInfo,
+    stream_proposal_account: &AccountInfo,
+    multisig_account: &AccountInfo,
+    key: &Pubkey,
+    update: impl FnOnce(&Member) -> Member,
+) -> ProgramResult {
+    MultiSignatureVault::with_members_mut(multisig_account, |multisig_info, members| {
+        let Some(slot) = MultiSignatureVault::member_slot(members, key) else {
+            log!("Member to update is no longer part of the multisig");
+            return Err(TreasuryError::MemberNotFound.into());
+        };
+
+        let updated = update(&members[slot]);
+
+        // Membership, weights or roles may have changed since the proposal was created.
+        let new_total_weight = multisig_info.total_weight_with(members, slot, &updated)?;
+        if multisig_info.max_threshold() > new_total_weight {
+            log!("Cannot update member: threshold {} would exceed total weight {}", multisig_info.max_threshold(), new_total_weight);
+            return Err(TreasuryError::InvalidThreshold.into());
+        };
+
+        if !MultiSignatureVault::keeps_role_holders(members, slot, &updated) {
+            log!("Cannot update member: no member would be left to propose or execute");
+            return Err(TreasuryError::MissingRoleHolder.into());
+        };
+
+        multisig_info.update_member(members, slot, updated)
+    })?;
+
+    let mut multisig_info = MultiSignatureVault::from_account_info_mut(multisig_account)?;
+    let mut stream_proposal = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+    mark_proposal_executed(&mut stream_proposal, &mut multisig_info, executor.key())?;
+
+    log!("Member updated! Total weight: {}", multisig_info.total_weight);
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
-use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, pubkey::Pubkey, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
+use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{clock::Clock, rent::Rent, Sysvar}, *};
 
-use crate::states::{MultiSignatureVault, StreamProposal, ProposalStatus};
+use crate::{error::TreasuryError, pda, states::{Member, MultiSignatureVault, ProposalAction, StreamProposal, StreamType, ProposalStatus}};
 
 pub fn process_vote_on_proposal(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
 
@@ -29,141 +29,98 @@ pub fn process_vote_on_proposal(accounts: &[AccountInfo], instruction_data: &[u8
     );
 
     if vote_type > 1 {
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::InvalidVoteType.into());
     }
 
-    let (stream_proposal_account_pda, _bump) = pubkey::find_program_address(
-        &[b"stream_proposal", proposal_id.to_le_bytes().as_ref(), multisig_id.to_le_bytes().as_ref()],
-        &crate::ID
-    );
+    let ballot = open_ballot(stream_proposal_account, multisig_account, proposal_id, multisig_id, false)?;
 
-    if *stream_proposal_account.key() != stream_proposal_account_pda {
-        return Err(ProgramError::InvalidAccountData);
-    }
+    let members = MultiSignatureVault::members(multisig_account)?;
 
-    let mut stream_proposal_account_info = StreamProposal::from_account_info_mut(stream_proposal_account)?;
+    let voter_slot = MultiSignatureVault::member_slot(&members, voter.key())
+        .ok_or(TreasuryError::NotMultisigMember)?;
 
-    if stream_proposal_account_info.multisig_id != multisig_id {
-        return Err(ProgramError::InvalidAccountData);
+    if !members[voter_slot].has_role(Member::VOTER) {
+        return Err(TreasuryError::MissingMemberRole.into());
     }
 
-    let current_time = Clock::get()?.unix_timestamp;
+    StreamProposal::with_votes_mut(stream_proposal_account, |stream_proposal_account_info, votes| {
+        stream_proposal_account_info.record_vote(votes, &members, voter_slot, vote_type == 0)?;
+        stream_proposal_account_info.required_threshold = ballot.required_threshold;
+        stream_proposal_account_info.update_status(ballot.current_time, ballot.timelock_delay)
+    })
+}
+
+/// What a vote on an open proposal is counted against.
+pub struct Ballot {
+    pub current_time: i64,
+    pub required_threshold: u64,
+    pub timelock_delay: i64,
+}
 
-    if current_time > stream_proposal_account_info.voting_deadline {
-        return Err(ProgramError::Custom(2001)); 
+/// Checks that `stream_proposal_account` is proposal `proposal_id` of the multisig
+/// `multisig_id` and still open for voting. Proposals are open while `Active` and, so members
+/// can veto them, while `Approved` but timelocked; `allow_approved` opens every `Approved`
+/// proposal, for members revising votes they already cast.
+pub fn open_ballot(
+    stream_proposal_account: &AccountInfo,
+    multisig_account: &AccountInfo,
+    proposal_id: u64,
+    multisig_id: u64,
+    allow_approved: bool,
+) -> Result<Ballot, ProgramError> {
+    let (stream_proposal_account_pda, _bump) = pda::find_stream_proposal(multisig_account.key(), proposal_id);
+
+    if *stream_proposal_account.key() != stream_proposal_account_pda {
+        return Err(TreasuryError::InvalidProposalAccount.into());
     }
 
-    let proposal_status = ProposalStatus::try_from(&stream_proposal_account_info.status)?;
-    if proposal_status != ProposalStatus::Active {
-        return Err(ProgramError::Custom(2003)); 
+    let stream_proposal_account_info = StreamProposal::from_account_info(stream_proposal_account)?;
+
+    if stream_proposal_account_info.multisig_id != multisig_id {
+        return Err(TreasuryError::MultisigIdMismatch.into());
     }
 
-    let multisig_account_info = MultiSignatureVault::from_account_info(multisig_account)?;
+    let current_time = Clock::get()?.unix_timestamp;
 
-    let (multisig_info_pda, _multisig_info_bump) = pubkey::find_program_address(
-        &[b"multisig_info", multisig_account_info.admin.as_ref(), multisig_id.to_le_bytes().as_ref()],
-        &crate::ID
-    );
+    let proposal_status = ProposalStatus::try_from(&stream_proposal_account_info.status)?;
+    let in_timelock = current_time < stream_proposal_account_info.executable_after;
 
-    if *multisig_account.key() != multisig_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+    // A timelock may run past the voting deadline; members can veto until it ends.
+    if current_time > stream_proposal_account_info.voting_deadline && !(proposal_status == ProposalStatus::Approved && in_timelock) {
+        return Err(TreasuryError::VotingDeadlinePassed.into());
     }
 
-    if !multisig_account_info.is_active {
-        return Err(ProgramError::Custom(2004)); 
-    }
+    let open = match proposal_status {
+        ProposalStatus::Active => true,
+        ProposalStatus::Approved => allow_approved || in_timelock,
+        _ => false,
+    };
 
-    let voter_key = *voter.key();
-    let mut is_multisig_member = false;
-    
-    for member in multisig_account_info.member_keys {
-        if voter_key == member {
-            is_multisig_member = true;
-            break;
-        }
+    if !open {
+        return Err(TreasuryError::ProposalNotActive.into());
     }
 
-    if !is_multisig_member {
-        return Err(ProgramError::Custom(2005)); 
-    }
+    let multisig_account_info = MultiSignatureVault::from_account_info(multisig_account)?;
 
-    for approval in stream_proposal_account_info.approvals {
-        if approval == voter_key {
-            return Err(ProgramError::Custom(2002)); 
-        }
-    }
-    
-    for rejection in stream_proposal_account_info.rejections {
-        if rejection == voter_key {
-            return Err(ProgramError::Custom(2002)); 
-        }
-    }
+    let (multisig_info_pda, _multisig_info_bump) = pda::find_multisig_info(&multisig_account_info.creator, multisig_id);
 
-    match vote_type {
-        0 => { 
-            let mut vote_recorded = false;
-            
-            for i in 0..10 {
-                if stream_proposal_account_info.approvals[i] == Pubkey::default() {
-                    stream_proposal_account_info.approvals[i] = voter_key;
-                    vote_recorded = true;
-                    
-                    stream_proposal_account_info.approval_count = 
-                        stream_proposal_account_info.approval_count
-                        .checked_add(1)
-                        .ok_or(ProgramError::ArithmeticOverflow)?;
-                    
-                    stream_proposal_account_info.total_vote_count = 
-                        stream_proposal_account_info.total_vote_count
-                        .checked_add(1)
-                        .ok_or(ProgramError::ArithmeticOverflow)?;
-                    
-                    break;
-                }
-            }
-
-            if !vote_recorded {
-                return Err(ProgramError::InvalidAccountData); 
-            }
-        },
-        1 => { 
-            let mut vote_recorded = false;
-            
-            for i in 0..10 {
-                if stream_proposal_account_info.rejections[i] == Pubkey::default() {
-                    stream_proposal_account_info.rejections[i] = voter_key;
-                    vote_recorded = true;
-                    
-                    stream_proposal_account_info.rejection_count = 
-                        stream_proposal_account_info.rejection_count
-                        .checked_add(1)
-                        .ok_or(ProgramError::ArithmeticOverflow)?;
-                    
-                    stream_proposal_account_info.total_vote_count = 
-                        stream_proposal_account_info.total_vote_count
-                        .checked_add(1)
-                        .ok_or(ProgramError::ArithmeticOverflow)?;
-                    
-                    break;
-                }
-            }
-
-            if !vote_recorded {
-                return Err(ProgramError::InvalidAccountData); 
-            }
-        },
-        _ => {
-            return Err(ProgramError::InvalidInstructionData);
-        }
+    if *multisig_account.key() != multisig_info_pda {
+        return Err(TreasuryError::InvalidMultisigAccount.into());
     }
 
-    if stream_proposal_account_info.rejection_count >= stream_proposal_account_info.required_threshold {
-        stream_proposal_account_info.status = ProposalStatus::Rejected as u8;
-    }
+    // The amount tier the bound payout falls in is applied with the vault's current policy.
+    let stream_type = StreamType::try_from(&stream_proposal_account_info.stream_type)?;
+    let action = ProposalAction::try_from_payload(&stream_type, &StreamProposal::payload(stream_proposal_account)?)
+        .map_err(|_| TreasuryError::InvalidProposalPayload)?;
 
-    else if stream_proposal_account_info.approval_count >= stream_proposal_account_info.required_threshold {
-        stream_proposal_account_info.status = ProposalStatus::Approved as u8;
+    // A paused vault keeps voting on governance, unpausing included, but not on payouts.
+    if !multisig_account_info.is_active && action.mint().is_some() {
+        return Err(TreasuryError::MultisigInactive.into());
     }
 
-    Ok(())
-}
\ No newline at end of file
+    Ok(Ballot {
+        current_time,
+        required_threshold: multisig_account_info.required_threshold(&stream_type, &action)?,
+        timelock_delay: multisig_account_info.timelock_delay,
+    })
+}
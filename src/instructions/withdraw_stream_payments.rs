@@ -1,68 +1,75 @@
 use pinocchio::{account_info::AccountInfo, instruction::Signer, program_error::ProgramError, sysvars::{clock::Clock, Sysvar}, *};
-use pinocchio_token::instructions::Transfer;
+use pinocchio_token::{instructions::Transfer, state::TokenAccount};
 
-use crate::states::PaymentStreamingInfo;
+use crate::{error::TreasuryError, pda, states::{MultiSignatureVault, PaymentStreamingInfo}};
 
 pub fn process_withdraw_stream_payments(accounts: &[AccountInfo]) -> ProgramResult {
 
-    let [recipient, sender_token_account, recipient_token_account, payment_stream_info, token_program] = accounts else {
+    let [recipient, sender_token_account, recipient_token_account, payment_stream_info, token_program, sender_multisig @ ..] = accounts else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     if !recipient.is_signer() {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(ProgramError::MissingRequiredSignature);
     };
 
     let mut payment_stream_account_info = PaymentStreamingInfo::from_account_info_mut(payment_stream_info)?;
 
     if !payment_stream_account_info.is_active {
         msg!("Payment stream is not active");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::StreamInactive.into());
     };
 
     if *recipient.key() != payment_stream_account_info.recipient {
         msg!("Only the recipient can withdraw from the stream");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::UnauthorizedStreamParty.into());
     };
 
     if *sender_token_account.key() != payment_stream_account_info.sender_token_account {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidStreamAccount.into());
     };
 
     if *recipient_token_account.key() != payment_stream_account_info.recipient_token_account {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::TokenAccountMismatch.into());
+    };
+
+    // Treasury-funded streams pay out of an escrow the stream owns; their multisig must not be paused.
+    if TokenAccount::from_account_info(sender_token_account)?.owner() == payment_stream_info.key() {
+        let [multisig_account, ..] = sender_multisig else {
+            msg!("Treasury-funded streams need the sending multisig account");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if *multisig_account.key() != payment_stream_account_info.sender {
+            return Err(TreasuryError::InvalidMultisigAccount.into());
+        };
+
+        MultiSignatureVault::check_not_paused(multisig_account)?;
     };
 
     let current_time = Clock::get()?.unix_timestamp;
 
     if current_time < payment_stream_account_info.start_time {
         msg!("Stream not started yet!");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::StreamNotStarted.into());
     };
 
     let withdrawable_amount = calculate_withdrawable_amount(&payment_stream_account_info, current_time)?;
 
     if withdrawable_amount == 0 {
         msg!("No tokens available for withdrawal");
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(TreasuryError::NothingToWithdraw.into());
     };
 
-    let (payment_stream_info_pda, payment_stream_info_bump) = pubkey::find_program_address(
-        &[
-            b"payment_stream_info", 
-            payment_stream_account_info.sender.as_ref(), 
-            payment_stream_account_info.recipient.as_ref()
-        ],
-        &crate::ID
-    );
+    let (payment_stream_info_pda, payment_stream_info_bump) = pda::find_payment_stream(&payment_stream_account_info.sender, &payment_stream_account_info.recipient);
 
     if *payment_stream_info.key() != payment_stream_info_pda {
-        return Err(ProgramError::InvalidAccountData);
+        return Err(TreasuryError::InvalidStreamAccount.into());
     }
 
     let bump = &[payment_stream_info_bump];
     let seeds = seeds!(
-        b"payment_stream_info", 
+        pda::PAYMENT_STREAM_SEED,
         payment_stream_account_info.sender.as_ref(), 
         payment_stream_account_info.recipient.as_ref(),
         bump
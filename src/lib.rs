@@ -7,7 +7,9 @@ declare_id!("4taWcHcTu9CbPv6JiB9HNxU9aQrni7dARoDtyv9GsWQ6");
 
 entrypoint!(process_instruction);
 
+pub mod error;
 pub mod instructions;
+pub mod pda;
 pub mod states;
 
 fn process_instruction(
@@ -29,6 +31,29 @@ fn process_instruction(
         MultiSignatureInstructions::ClaimVestedTokens => instructions::claim_vested_tokens::process_claim_vested_tokens(accounts, instruction_data)?,
         MultiSignatureInstructions::DepositFundsVault => instructions::deposit_tokens_vesting::process_deposit_token_vesting_vault(accounts, instruction_data)?,
         MultiSignatureInstructions::CancelStream => instructions::cancel_stream::process_cancel_stream(accounts)?,
+        MultiSignatureInstructions::RemoveMember => instructions::remove_member::process_remove_member(accounts)?,
+        MultiSignatureInstructions::UpdateConfig => instructions::update_config::process_update_config(accounts)?,
+        MultiSignatureInstructions::TransferTokens => instructions::token_transfers::process_transfer_tokens(accounts, instruction_data)?,
+        MultiSignatureInstructions::InitializeBatchState => instructions::init_batch_payments::process_initialize_batch_state(accounts, instruction_data)?,
+        MultiSignatureInstructions::BatchTransfer => instructions::batch_payments::process_batch_transfer(accounts, instruction_data)?,
+        MultiSignatureInstructions::InitVestingContract => instructions::init_vesting::process_init_vesting_contract(accounts, instruction_data)?,
+        MultiSignatureInstructions::InitBeneficiary => instructions::init_beneficiary::process_init_beneficiary(accounts, instruction_data)?,
+        MultiSignatureInstructions::MigrateAccount => instructions::migrate_account::process_migrate_account(accounts)?,
+        MultiSignatureInstructions::UpdateMemberWeight => instructions::update_member::process_update_member_weight(accounts)?,
+        MultiSignatureInstructions::UpdateMemberRoles => instructions::update_member::process_update_member_roles(accounts)?,
+        MultiSignatureInstructions::CancelProposal => instructions::cancel_proposal::process_cancel_proposal(accounts)?,
+        MultiSignatureInstructions::RevokeVote => instructions::change_vote::process_revoke_vote(accounts, instruction_data)?,
+        MultiSignatureInstructions::ChangeVote => instructions::change_vote::process_change_vote(accounts, instruction_data)?,
+        MultiSignatureInstructions::ExpireProposal => instructions::expire_proposal::process_expire_proposal(accounts)?,
+        MultiSignatureInstructions::CloseProposal => instructions::close_proposal::process_close_proposal(accounts)?,
+        MultiSignatureInstructions::EmergencyPause => instructions::emergency_pause::process_emergency_pause(accounts)?,
+        MultiSignatureInstructions::Unpause => instructions::emergency_pause::process_unpause(accounts)?,
+        MultiSignatureInstructions::SetGuardian => instructions::emergency_pause::process_set_guardian(accounts)?,
+        MultiSignatureInstructions::TransferAdmin => instructions::transfer_admin::process_transfer_admin(accounts)?,
+        MultiSignatureInstructions::AcceptAdmin => instructions::transfer_admin::process_accept_admin(accounts)?,
+        MultiSignatureInstructions::SetDepositAllowlist => instructions::deposit_funds::process_set_deposit_allowlist(accounts)?,
+        MultiSignatureInstructions::AllowDepositor => instructions::deposit_funds::process_allow_depositor(accounts)?,
+        MultiSignatureInstructions::CancelTreasuryStream => instructions::cancel_stream::process_cancel_treasury_stream(accounts)?,
     }
 
     Ok(())
@@ -0,0 +1,145 @@
+use pinocchio::pubkey::Pubkey;
+
+// Seeds for every address the program derives. Each multisig's accounts are seeded with its
+// `multisig_info` account key, so two admins reusing a numeric `multisig_id` never share a
+// treasury or proposal. The same functions derive addresses on-chain and in Rust clients.
+
+pub const MULTISIG_INFO_SEED: &[u8] = b"multisig_info";
+pub const TREASURY_VAULT_SEED: &[u8] = b"multisig_vault";
+pub const STREAM_PROPOSAL_SEED: &[u8] = b"stream_proposal";
+pub const PAYMENT_STREAM_SEED: &[u8] = b"payment_stream_info";
+pub const STREAM_ESCROW_SEED: &[u8] = b"stream_escrow";
+pub const VESTING_VAULT_SEED: &[u8] = b"vault";
+pub const VESTING_RECORD_SEED: &[u8] = b"vesting_record_info";
+pub const BATCH_STATE_SEED: &[u8] = b"batch_state";
+pub const CREATOR_TOKEN_ACCOUNT_SEED: &[u8] = b"creator_token_account";
+pub const RECIPIENT_TOKEN_ACCOUNT_SEED: &[u8] = b"recepient_token_account";
+pub const DEPOSIT_RECEIPT_SEED: &[u8] = b"deposit_receipt";
+
+/// Derives a program address for `seeds`, returning it with its bump.
+pub fn find_program_address(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    #[cfg(target_os = "solana")]
+    {
+        pinocchio::pubkey::find_program_address(seeds, &crate::ID)
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        let program_id = solana_pubkey::Pubkey::new_from_array(crate::ID);
+        let (address, bump) = solana_pubkey::Pubkey::find_program_address(seeds, &program_id);
+        (address.to_bytes(), bump)
+    }
+}
+
+/// The multisig account created by `creator` under `multisig_id`. The address stays put when
+/// the admin role changes hands, so it is always derived from the vault's `creator`.
+pub fn find_multisig_info(creator: &Pubkey, multisig_id: u64) -> (Pubkey, u8) {
+    find_program_address(&[MULTISIG_INFO_SEED, creator.as_ref(), multisig_id.to_le_bytes().as_ref()])
+}
+
+/// The treasury token account of `multisig` for `mint`.
+pub fn find_treasury_vault(multisig: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[TREASURY_VAULT_SEED, multisig.as_ref(), mint.as_ref()])
+}
+
+/// Treasury address used before vaults were namespaced by their multisig account. Vaults
+/// keep their address, so the program always checks against `treasury_vault` on the multisig.
+pub fn find_legacy_treasury_vault(mint: &Pubkey, multisig_id: u64) -> (Pubkey, u8) {
+    find_program_address(&[TREASURY_VAULT_SEED, mint.as_ref(), multisig_id.to_le_bytes().as_ref()])
+}
+
+/// Proposal `proposal_id` of `multisig`.
+pub fn find_stream_proposal(multisig: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    find_program_address(&[STREAM_PROPOSAL_SEED, multisig.as_ref(), proposal_id.to_le_bytes().as_ref()])
+}
+
+/// Address of a proposal created before the vault assigned IDs, seeded with the numeric
+/// ids alone. Such proposals can no longer be voted on or executed, only expired or closed.
+pub fn find_legacy_stream_proposal(proposal_id: u64, multisig_id: u64) -> (Pubkey, u8) {
+    find_program_address(&[STREAM_PROPOSAL_SEED, proposal_id.to_le_bytes().as_ref(), multisig_id.to_le_bytes().as_ref()])
+}
+
+/// Receipt of the deposits `depositor` made into the treasury of `multisig`.
+pub fn find_deposit_receipt(multisig: &Pubkey, depositor: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[DEPOSIT_RECEIPT_SEED, multisig.as_ref(), depositor.as_ref()])
+}
+
+/// Stream from `sender` to `recipient`; the sender is the multisig account for
+/// treasury-funded streams.
+pub fn find_payment_stream(sender: &Pubkey, recipient: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[PAYMENT_STREAM_SEED, sender.as_ref(), recipient.as_ref()])
+}
+
+/// Token account holding the unstreamed balance of a treasury-funded stream.
+pub fn find_stream_escrow(payment_stream: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[STREAM_ESCROW_SEED, payment_stream.as_ref()])
+}
+
+/// Vesting contract of `creator`; the creator is the multisig account for treasury-funded
+/// vesting.
+pub fn find_vesting_contract(company_name: &[u8; 32], creator: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[company_name.as_ref(), creator.as_ref()])
+}
+
+/// Token account holding the tokens of the vesting contract `company_name` of `creator`,
+/// seeded with `[VESTING_VAULT_SEED, creator, company_name, mint]`. For treasury-funded
+/// vesting the creator is the multisig account, so no two multisigs or companies share a
+/// vault. Contracts created before the company name was part of the seeds keep the vault
+/// recorded in their `vault_account`, seeded with the mint and creator alone.
+pub fn find_vesting_vault(creator: &Pubkey, company_name: &[u8; 32], mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[VESTING_VAULT_SEED, creator.as_ref(), company_name.as_ref(), mint.as_ref()])
+}
+
+pub fn find_vesting_record(vesting_contract: &Pubkey, beneficiary: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[VESTING_RECORD_SEED, vesting_contract.as_ref(), beneficiary.as_ref()])
+}
+
+pub fn find_beneficiary_token_account(beneficiary: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[beneficiary.as_ref(), mint.as_ref()])
+}
+
+pub fn find_batch_state(creator: &Pubkey, mint: &Pubkey, batch_id: u64) -> (Pubkey, u8) {
+    find_program_address(&[BATCH_STATE_SEED, creator.as_ref(), mint.as_ref(), batch_id.to_le_bytes().as_ref()])
+}
+
+pub fn find_creator_token_account(creator: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[CREATOR_TOKEN_ACCOUNT_SEED, creator.as_ref(), mint.as_ref()])
+}
+
+pub fn find_recipient_token_account(recipient: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[RECIPIENT_TOKEN_ACCOUNT_SEED, recipient.as_ref(), mint.as_ref()])
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    const MINT: Pubkey = [2u8; 32];
+
+    #[test]
+    fn test_same_multisig_id_does_not_collide() {
+        let (first, _) = find_multisig_info(&[1u8; 32], 7);
+        let (second, _) = find_multisig_info(&[3u8; 32], 7);
+        assert_ne!(first, second);
+
+        assert_ne!(find_treasury_vault(&first, &MINT), find_treasury_vault(&second, &MINT));
+        assert_ne!(find_stream_proposal(&first, 0), find_stream_proposal(&second, 0));
+    }
+
+    #[test]
+    fn test_vesting_vaults_are_per_contract() {
+        let (multisig, _) = find_multisig_info(&[1u8; 32], 7);
+        let (other_multisig, _) = find_multisig_info(&[3u8; 32], 7);
+        let (vault, _) = find_vesting_vault(&multisig, &[4u8; 32], &MINT);
+
+        assert_ne!(vault, find_vesting_vault(&multisig, &[5u8; 32], &MINT).0);
+        assert_ne!(vault, find_vesting_vault(&other_multisig, &[4u8; 32], &MINT).0);
+    }
+
+    #[test]
+    fn test_treasury_vault_moved_off_legacy_seeds() {
+        let (multisig, _) = find_multisig_info(&[1u8; 32], 7);
+
+        assert_ne!(find_treasury_vault(&multisig, &MINT).0, find_legacy_treasury_vault(&MINT, 7).0);
+    }
+}
@@ -0,0 +1,90 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, sysvars::{rent::Rent, Sysvar}, ProgramResult};
+use pinocchio_system::instructions::Transfer;
+
+use crate::error::TreasuryError;
+
+/// Every program-owned account starts with an 8-byte type discriminator followed by a
+/// layout version byte, so an account of one type can never be loaded as another.
+/// Discriminators are the first 8 bytes of `sha256("account:<TypeName>")`.
+pub trait AccountHeader {
+    const DISCRIMINATOR: [u8; 8];
+    const VERSION: u8;
+}
+
+pub const DISCRIMINATOR_LEN: usize = 8;
+pub const VERSION_OFFSET: usize = DISCRIMINATOR_LEN;
+
+/// Checks that `account` is owned by this program, at least `size` bytes long and tagged
+/// as `T` at the current layout version.
+pub fn check_account<T: AccountHeader>(account: &AccountInfo, size: usize) -> ProgramResult {
+    if *account.owner() != crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    };
+
+    if account.data_len() < size {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    let data = account.try_borrow_data()?;
+
+    if data[..DISCRIMINATOR_LEN] != T::DISCRIMINATOR {
+        return Err(TreasuryError::InvalidAccountDiscriminator.into());
+    };
+
+    if data[VERSION_OFFSET] != T::VERSION {
+        return Err(TreasuryError::UnsupportedAccountVersion.into());
+    };
+
+    Ok(())
+}
+
+/// Grows or shrinks `account` to `new_size`, topping its lamports up to the new rent-exempt
+/// minimum from `payer`. Lamports freed by shrinking stay on the account.
+pub fn resize_account(account: &AccountInfo, payer: &AccountInfo, new_size: usize) -> ProgramResult {
+    let required = Rent::get()?.minimum_balance(new_size);
+    let shortfall = required.saturating_sub(account.lamports());
+
+    if shortfall > 0 {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: shortfall,
+        }.invoke()?;
+    };
+
+    account.resize(new_size)
+}
+
+/// Tags a freshly created account as `T`. Fails if the account already carries a header.
+pub fn write_header<T: AccountHeader>(account: &AccountInfo, size: usize) -> ProgramResult {
+    if *account.owner() != crate::ID {
+        return Err(ProgramError::InvalidAccountOwner);
+    };
+
+    if account.data_len() < size {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    let mut data = account.try_borrow_mut_data()?;
+
+    if data[..=VERSION_OFFSET].iter().any(|byte| *byte != 0) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    };
+
+    data[..DISCRIMINATOR_LEN].copy_from_slice(&T::DISCRIMINATOR);
+    data[VERSION_OFFSET] = T::VERSION;
+
+    Ok(())
+}
+
+/// Closes `account`, moving all of its lamports to `recipient`.
+pub fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> ProgramResult {
+    let lamports = account.lamports();
+
+    *recipient.try_borrow_mut_lamports()? = recipient.lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    *account.try_borrow_mut_lamports()? = 0;
+
+    account.close()
+}
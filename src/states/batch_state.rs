@@ -1,8 +1,12 @@
 use pinocchio::{account_info::{AccountInfo, RefMut, Ref}, program_error::ProgramError, pubkey::Pubkey};
 
+use crate::{error::TreasuryError, states::{check_account, write_header, AccountHeader}};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
 pub struct BatchState {
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub batch_id: u64,
     pub authority: Pubkey,
     pub mint: Pubkey,
@@ -12,13 +16,16 @@ pub struct BatchState {
     pub bump: u8,
 }
 
+impl AccountHeader for BatchState {
+    const DISCRIMINATOR: [u8; 8] = [45, 46, 153, 185, 167, 42, 128, 10];
+    const VERSION: u8 = 1;
+}
+
 impl BatchState {
     pub const LEN: usize = core::mem::size_of::<BatchState>();
 
     pub fn from_account_info(accounts: &AccountInfo) -> Result<Ref<Self>, ProgramError> { 
-        if accounts.data_len() < Self::LEN { 
-            return Err(ProgramError::InvalidAccountData); 
-        } 
+        check_account::<Self>(accounts, Self::LEN)?;
   
         Ok(Ref::map(accounts.try_borrow_data()?, |data| unsafe {
             &*(data.as_ptr() as *const Self)
@@ -26,15 +33,19 @@ impl BatchState {
     } 
 
     pub fn from_account_info_mut(accounts: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if accounts.data_len() < Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_account::<Self>(accounts, Self::LEN)?;
 
         Ok(RefMut::map(accounts.try_borrow_mut_data()?, |data| unsafe {
             &mut *(data.as_mut_ptr() as *mut Self)
         }))
     }
 
+    /// Tags a freshly created account as `BatchState` and returns it for initialization.
+    pub fn initialize(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        write_header::<Self>(account, Self::LEN)?;
+        Self::from_account_info_mut(account)
+    }
+
     pub fn is_completed(&self) -> bool {
         self.is_completed != 0
     }
@@ -52,14 +63,14 @@ impl BatchState {
 
     pub fn validate_authority(&self, expected_authority: &Pubkey) -> Result<(), ProgramError> {
         if self.authority != *expected_authority {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(TreasuryError::InvalidBatchAccount.into());
         }
         Ok(())
     }
 
     pub fn validate_mint(&self, expected_mint: &Pubkey) -> Result<(), ProgramError> {
         if self.mint != *expected_mint {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(TreasuryError::InvalidBatchAccount.into());
         }
         Ok(())
     }
@@ -1,6 +1,6 @@
-use pinocchio::{account_info::{AccountInfo, RefMut, Ref}, program_error::ProgramError, pubkey::Pubkey};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
 
-const MAX_RECIPIENTS: usize = 10;
+pub const MAX_RECIPIENTS: usize = 10;
 
 #[derive(Clone, Copy, PartialEq)]
 #[repr(C)]
@@ -13,6 +13,7 @@ pub struct BatchTransfer {
 
 impl BatchTransfer {
     pub const LEN: usize = core::mem::size_of::<Self>();
+    pub const HEADER_LEN: usize = 17;
 
     pub fn new(batch_id: u64, amount_per_recipient: u64) -> Self {
         Self {
@@ -23,6 +24,42 @@ impl BatchTransfer {
         }
     }
 
+    /// Parses `batch_id u64 | amount_per_recipient u64 | num_recipients u8` followed by
+    /// exactly `num_recipients` recipient wallets.
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::HEADER_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let num_recipients = data[16];
+        if num_recipients == 0
+            || num_recipients as usize > MAX_RECIPIENTS
+            || data.len() != Self::HEADER_LEN + num_recipients as usize * 32
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let batch_id = u64::from_le_bytes(data[0..8].try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+        let amount_per_recipient = u64::from_le_bytes(data[8..16].try_into().map_err(|_| ProgramError::InvalidInstructionData)?);
+
+        if amount_per_recipient == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut batch_transfer = Self::new(batch_id, amount_per_recipient);
+        for chunk in data[Self::HEADER_LEN..].chunks_exact(32) {
+            let recipient: Pubkey = chunk.try_into().map_err(|_| ProgramError::InvalidInstructionData)?;
+
+            if recipient == Pubkey::default() {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            batch_transfer.add_recipient(recipient)?;
+        }
+
+        Ok(batch_transfer)
+    }
+
     pub fn add_recipient(&mut self, recipient: Pubkey) -> Result<(), ProgramError> {
         if self.num_recipients >= MAX_RECIPIENTS as u8 {
             return Err(ProgramError::InvalidInstructionData);
@@ -40,24 +77,28 @@ impl BatchTransfer {
     pub fn total_amount_needed(&self) -> u64 {
         self.amount_per_recipient.saturating_mul(self.num_recipients as u64)
     }
+}
 
-    pub fn from_account_info(account: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
-        if account.data_len() < Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
+#[cfg(test)]
+mod testing {
+    use super::*;
 
-        Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
-            &*(data.as_ptr() as *const Self)
-        }))
-    }
+    #[test]
+    fn test_batch_transfer_requires_exact_recipient_list() {
+        let mut data = vec![0u8; BatchTransfer::HEADER_LEN];
+        data[0..8].copy_from_slice(&7u64.to_le_bytes());
+        data[8..16].copy_from_slice(&250u64.to_le_bytes());
+        data[16] = 2;
+        data.extend_from_slice(&[1u8; 32]);
+        assert!(BatchTransfer::try_from_bytes(&data).is_err());
 
-    pub fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if account.data_len() < Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        data.extend_from_slice(&[2u8; 32]);
+        let batch_transfer = BatchTransfer::try_from_bytes(&data).unwrap();
+        assert_eq!(batch_transfer.batch_id, 7);
+        assert_eq!(batch_transfer.get_active_recipients(), &[[1u8; 32], [2u8; 32]]);
+        assert_eq!(batch_transfer.total_amount_needed(), 500);
 
-        Ok(RefMut::map(account.try_borrow_mut_data()?, |data| unsafe {
-            &mut *(data.as_mut_ptr() as *mut Self)
-        }))
+        data.extend_from_slice(&[3u8; 32]);
+        assert!(BatchTransfer::try_from_bytes(&data).is_err());
     }
-}
\ No newline at end of file
+}
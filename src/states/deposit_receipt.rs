@@ -0,0 +1,94 @@
+use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError, pubkey::Pubkey};
+use shank::ShankAccount;
+
+use crate::states::{check_account, write_header, AccountHeader};
+
+/// Running total of what `depositor` has paid into the treasury of `multisig`, created on
+/// their first deposit. On vaults with `deposit_allowlist` set only depositors whose receipt
+/// an `AllowDepositor` proposal created or updated with `is_allowed` may deposit.
+#[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+#[repr(C)]
+pub struct DepositReceipt {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub multisig: Pubkey,
+    pub depositor: Pubkey,
+    pub total_deposited: u64,
+    pub deposit_count: u64,
+    pub first_deposit_at: i64,        // 0 until the first deposit
+    pub last_deposit_at: i64,
+    pub is_allowed: bool,             // Only consulted while the vault's allowlist is on
+    pub bump: u8,
+}
+
+impl AccountHeader for DepositReceipt {
+    const DISCRIMINATOR: [u8; 8] = [64, 175, 24, 183, 138, 109, 70, 78];
+    const VERSION: u8 = 1;
+}
+
+impl DepositReceipt {
+    pub const SIZE: usize = core::mem::size_of::<DepositReceipt>();
+
+    pub fn from_account_info(account: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        check_account::<Self>(account, Self::SIZE)?;
+
+        Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const Self)
+        }))
+    }
+
+    pub fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        check_account::<Self>(account, Self::SIZE)?;
+
+        Ok(RefMut::map(account.try_borrow_mut_data()?, |data| unsafe {
+            &mut *(data.as_mut_ptr() as *mut Self)
+        }))
+    }
+
+    /// Tags a freshly created account as `DepositReceipt` and returns it for initialization.
+    pub fn initialize(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        write_header::<Self>(account, Self::SIZE)?;
+        Self::from_account_info_mut(account)
+    }
+
+    /// Adds a deposit of `amount` made at `now` to the running totals.
+    pub fn record_deposit(&mut self, amount: u64, now: i64) -> Result<(), ProgramError> {
+        self.total_deposited = self.total_deposited.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+        self.deposit_count = self.deposit_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+
+        if self.first_deposit_at == 0 {
+            self.first_deposit_at = now;
+        };
+        self.last_deposit_at = now;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_record_deposit_accumulates() {
+        let mut receipt = DepositReceipt {
+            discriminator: DepositReceipt::DISCRIMINATOR,
+            version: DepositReceipt::VERSION,
+            multisig: [1u8; 32],
+            depositor: [2u8; 32],
+            total_deposited: 0,
+            deposit_count: 0,
+            first_deposit_at: 0,
+            last_deposit_at: 0,
+            is_allowed: false,
+            bump: 255,
+        };
+
+        receipt.record_deposit(100, 10).unwrap();
+        receipt.record_deposit(50, 20).unwrap();
+
+        assert_eq!((receipt.total_deposited, receipt.deposit_count), (150, 2));
+        assert_eq!((receipt.first_deposit_at, receipt.last_deposit_at), (10, 20));
+        assert_eq!(receipt.record_deposit(u64::MAX, 30), Err(ProgramError::ArithmeticOverflow));
+    }
+}
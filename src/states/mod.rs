@@ -1,3 +1,6 @@
+pub mod account_header;
+pub use account_header::*;
+
 pub mod multi_signature_vault;
 pub use multi_signature_vault::*;
 
@@ -17,4 +20,10 @@ pub mod vesting_contract;
 pub use vesting_contract::*;
 
 pub mod vesting_record_info;
-pub use vesting_record_info::*;
\ No newline at end of file
+pub use vesting_record_info::*;
+
+pub mod proposal_payload;
+pub use proposal_payload::*;
+
+pub mod deposit_receipt;
+pub use deposit_receipt::*;
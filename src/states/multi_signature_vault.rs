@@ -1,17 +1,40 @@
 use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError, pubkey::Pubkey, *};
-use shank::ShankAccount;
+use shank::{ShankAccount, ShankType};
 
+use crate::{error::TreasuryError, states::{check_account, resize_account, write_header, AccountHeader, ProposalAction, StreamType}};
+
+/// Fixed header of a multisig account. `member_slots` `Member` entries follow it; a removed
+/// member's slot is zeroed rather than reused, so proposal vote bitmaps indexed by slot
+/// never attribute an old vote to a new member. `threshold` is in voting weight units.
+/// Payout proposals also need the threshold of the amount tier their outflow falls in.
+/// The address is seeded with `creator`, so the admin can be rotated or renounced in place.
+/// `is_active` is cleared while the vault is paused, freezing every outflow until an
+/// `Unpause` proposal approved by the full voting weight executes. Anyone may deposit into
+/// the treasury unless `deposit_allowlist` is set.
 #[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+#[repr(C)]
 pub struct MultiSignatureVault {
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub id: u64,
-    pub admin: Pubkey,
+    pub admin: Pubkey,                // Default key once the admin role is renounced
+    pub creator: Pubkey,              // Admin at creation; with `id`, seeds the account address
+    pub pending_admin: Pubkey,        // Approved new admin until they accept; default for none
     pub is_active: bool,              // Vault status
-    pub member_count: u64,
-    pub member_keys: [Pubkey; 10],
+    pub deposit_allowlist: bool,      // Only depositors allowed on their receipt may deposit
+    pub member_count: u64,            // Current members
+    pub member_slots: u64,            // Member entries stored after the header, removed ones included
+    pub total_weight: u64,            // Voting weight of all current voters
     pub threshold: u64,
-    pub proposal_expiry: i64, 
+    pub type_thresholds: [u64; 16],   // Per-StreamType overrides; 0 falls back to `threshold`
+    pub amount_tier_count: u64,       // Entries of `amount_tiers` in use
+    pub amount_tiers: [AmountTier; 8],
+    pub proposal_expiry: i64,         // Longest voting window a proposal may have, in seconds
+    pub timelock_delay: i64,          // Seconds an approved proposal waits before it can execute
+    pub guardian: Pubkey,             // May pause the vault alone; default key for none
+    pub pause_threshold: u64,         // Signing member weight that may pause; 0 disables
     pub total_proposals: u64,
-    pub treasury_vault: Pubkey, 
+    pub treasury_vault: Pubkey,       // Checked by address; vaults from before pda namespacing keep theirs
     pub created_at: i64,              // Timestamp
     pub last_updated: i64,            // Last modification
     pub minimum_balance: u64,         // Treasury threshold
@@ -21,16 +44,177 @@ pub struct MultiSignatureVault {
     pub treasury_vault_bump: u8,
 }
 
+/// Outflows of `mint` of at least `min_outflow` need `threshold` approval weight. Tiers for
+/// the same mint are ordered by ascending `min_outflow`, with thresholds that never decrease;
+/// the last one an outflow reaches applies.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ShankType)]
+#[repr(C)]
+pub struct AmountTier {
+    pub mint: Pubkey,
+    pub min_outflow: u64,
+    pub threshold: u64,
+}
+
+impl AmountTier {
+    pub const LEN: usize = core::mem::size_of::<AmountTier>();
+}
+
+/// One member slot stored after the `MultiSignatureVault` header. A member with no
+/// roles can be listed (a viewer) but cannot propose, vote or execute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Member {
+    pub key: Pubkey,
+    pub weight: u64,
+    pub roles: u8,
+}
+
+impl Member {
+    pub const LEN: usize = core::mem::size_of::<Member>();
+
+    /// May propose governance changes (membership, config, migrations).
+    pub const PROPOSER: u8 = 1 << 0;
+    /// May vote; only voters' weight counts toward thresholds.
+    pub const VOTER: u8 = 1 << 1;
+    /// May execute approved proposals.
+    pub const EXECUTOR: u8 = 1 << 2;
+    /// May propose treasury payouts.
+    pub const TREASURER: u8 = 1 << 3;
+    pub const ALL_ROLES: u8 = Self::PROPOSER | Self::VOTER | Self::EXECUTOR | Self::TREASURER;
+
+    pub fn new(key: Pubkey, weight: u64, roles: u8) -> Self {
+        Self { key, weight, roles }
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.key == Pubkey::default()
+    }
+
+    pub fn has_role(&self, role: u8) -> bool {
+        self.roles & role == role
+    }
+
+    /// Weight this member contributes to tallies and `total_weight`.
+    pub fn voting_weight(&self) -> u64 {
+        if self.has_role(Self::VOTER) { self.weight } else { 0 }
+    }
+}
+
+/// Layout the program shipped with, before accounts carried a discriminator and a version.
+/// It was not `repr(C)`, so it is declared exactly as it was deployed for the compiler to lay
+/// it out the same way. Member keys were stored inline and kept contiguous.
+#[derive(Clone, Copy)]
+struct MultiSignatureVaultV0 {
+    id: u64,
+    admin: Pubkey,
+    is_active: bool,
+    member_count: u64,
+    member_keys: [Pubkey; 10],
+    threshold: u64,
+    proposal_expiry: i64,
+    total_proposals: u64,
+    treasury_vault: Pubkey,
+    created_at: i64,
+    last_updated: i64,
+    minimum_balance: u64,
+    active_proposals: u64,
+    executed_proposals: u64,
+    bump: u8,
+    treasury_vault_bump: u8,
+}
+
+impl MultiSignatureVaultV0 {
+    /// Space the original layout allocated, which its padding estimate made larger than the struct.
+    const SPACE: usize = 480;
+
+    /// Reads an account still in the original layout. Accounts already carrying the current
+    /// header are up to date; anything else of another size is not a multisig account.
+    fn read(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.starts_with(&MultiSignatureVault::DISCRIMINATOR) {
+            return Err(TreasuryError::AccountUpToDate.into());
+        };
+
+        if data.len() != Self::SPACE {
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        Ok(unsafe { core::ptr::read_unaligned(data.as_ptr() as *const Self) })
+    }
+
+    /// Current header and member slots. Members get a weight of one and every role; per-type
+    /// thresholds, amount tiers, the timelock, the guardian and the deposit allowlist start
+    /// unset, and the admin is recorded as the `creator` the address was derived from.
+    fn upgrade(self) -> (MultiSignatureVault, Vec<Member>) {
+        let members: Vec<Member> = self.member_keys
+            .into_iter()
+            .take(self.member_count as usize)
+            .filter(|key| *key != Pubkey::default())
+            .map(|key| Member::new(key, 1, Member::ALL_ROLES))
+            .collect();
+
+        let vault = MultiSignatureVault {
+            discriminator: MultiSignatureVault::DISCRIMINATOR,
+            version: MultiSignatureVault::VERSION,
+            id: self.id,
+            admin: self.admin,
+            creator: self.admin,
+            pending_admin: Pubkey::default(),
+            is_active: self.is_active,
+            deposit_allowlist: false,
+            member_count: members.len() as u64,
+            member_slots: members.len() as u64,
+            total_weight: members.len() as u64,
+            threshold: self.threshold,
+            type_thresholds: [0; StreamType::MAX_TYPES],
+            amount_tier_count: 0,
+            amount_tiers: [AmountTier::default(); MultiSignatureVault::MAX_AMOUNT_TIERS],
+            proposal_expiry: self.proposal_expiry,
+            timelock_delay: 0,
+            guardian: Pubkey::default(),
+            pause_threshold: 0,
+            total_proposals: self.total_proposals,
+            treasury_vault: self.treasury_vault,
+            created_at: self.created_at,
+            last_updated: self.last_updated,
+            minimum_balance: self.minimum_balance,
+            active_proposals: self.active_proposals,
+            executed_proposals: self.executed_proposals,
+            bump: self.bump,
+            treasury_vault_bump: self.treasury_vault_bump,
+        };
+
+        (vault, members)
+    }
+}
+
+impl AccountHeader for MultiSignatureVault {
+    const DISCRIMINATOR: [u8; 8] = [200, 8, 202, 91, 77, 9, 89, 23];
+    const VERSION: u8 = 1;
+}
+
 impl MultiSignatureVault {
-    pub const SIZE: usize = 
+    pub const SIZE: usize =
+        8 +    // discriminator
+        1 +    // version
+        7 +    // padding after version
         8 +    // id
         32 +   // admin
+        32 +   // creator
+        32 +   // pending_admin
         1 +    // is_active
-        7 +    // padding after bool (Rust adds this automatically)
+        1 +    // deposit_allowlist
+        6 +    // padding after bools (Rust adds this automatically)
         8 +    // member_count
-        320 +  // member_keys
+        8 +    // member_slots
+        8 +    // total_weight
         8 +    // threshold
+        128 +  // type_thresholds
+        8 +    // amount_tier_count
+        384 +  // amount_tiers
         8 +    // proposal_expiry
+        8 +    // timelock_delay
+        32 +   // guardian
+        8 +    // pause_threshold
         8 +    // total_proposals
         32 +   // treasury_vault
         8 +    // created_at
@@ -42,10 +226,18 @@ impl MultiSignatureVault {
         1 +    // treasury_vault_bump
         6;     // explicit padding
 
+    /// Members a vault can hold at once.
+    pub const MAX_MEMBERS: u64 = u8::MAX as u64;
+    /// Member slots a vault can ever allocate, removed members included.
+    pub const MAX_MEMBER_SLOTS: u64 = 1024;
+    pub const MAX_AMOUNT_TIERS: usize = 8;
+
+    pub fn space(member_slots: usize) -> usize {
+        Self::SIZE + member_slots * Member::LEN
+    }
+
     pub fn from_account_info(account: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
             &*(data.as_ptr() as *const Self)
@@ -53,12 +245,408 @@ impl MultiSignatureVault {
     }
 
     pub fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(RefMut::map(account.try_borrow_mut_data()?, |data| unsafe {
             &mut *(data.as_mut_ptr() as *mut Self)
         }))
     }
-}
\ No newline at end of file
+
+    /// Every member slot; removed members read as a default key with zero weight.
+    pub fn members(account: &AccountInfo) -> Result<Ref<'_, [Member]>, ProgramError> {
+        let member_slots = Self::from_account_info(account)?.member_slots as usize;
+
+        if account.data_len() < Self::space(member_slots) {
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
+            core::slice::from_raw_parts(data[Self::SIZE..].as_ptr() as *const Member, member_slots)
+        }))
+    }
+
+    /// Runs `f` on the header and the member slots, borrowed mutably together.
+    pub fn with_members_mut<R>(
+        account: &AccountInfo,
+        f: impl FnOnce(&mut Self, &mut [Member]) -> Result<R, ProgramError>,
+    ) -> Result<R, ProgramError> {
+        check_account::<Self>(account, Self::SIZE)?;
+
+        let mut data = account.try_borrow_mut_data()?;
+        let (header, rest) = data.split_at_mut(Self::SIZE);
+        let vault = unsafe { &mut *(header.as_mut_ptr() as *mut Self) };
+
+        let members_len = vault.member_slots as usize * Member::LEN;
+        let members = rest.get_mut(..members_len).ok_or(ProgramError::InvalidAccountData)?;
+        let members = unsafe {
+            core::slice::from_raw_parts_mut(members.as_mut_ptr() as *mut Member, vault.member_slots as usize)
+        };
+
+        f(vault, members)
+    }
+
+    /// Appends `member` in a new slot, growing the account at `payer`'s expense.
+    pub fn push_member(account: &AccountInfo, payer: &AccountInfo, member: Member) -> Result<usize, ProgramError> {
+        let slot = Self::from_account_info(account)?.member_slots as usize;
+
+        resize_account(account, payer, Self::space(slot + 1))?;
+
+        let mut vault = Self::from_account_info_mut(account)?;
+        vault.member_slots += 1;
+        vault.member_count = vault.member_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        vault.total_weight = vault.total_weight.checked_add(member.voting_weight()).ok_or(ProgramError::ArithmeticOverflow)?;
+        drop(vault);
+
+        Self::with_members_mut(account, |_, members| {
+            members[slot] = member;
+            Ok(slot)
+        })
+    }
+
+    /// Slot `key` occupies, if it is a current member.
+    pub fn member_slot(members: &[Member], key: &Pubkey) -> Option<usize> {
+        if *key == Pubkey::default() {
+            return None;
+        }
+
+        members.iter().position(|member| member.key == *key)
+    }
+
+    pub fn is_member(members: &[Member], key: &Pubkey) -> bool {
+        Self::member_slot(members, key).is_some()
+    }
+
+    /// Removes `member`, leaving its slot empty, and returns the slot it held.
+    pub fn remove_member(&mut self, members: &mut [Member], member: &Pubkey) -> Result<usize, ProgramError> {
+        let slot = Self::member_slot(members, member).ok_or(ProgramError::InvalidAccountData)?;
+
+        self.total_weight = self.total_weight.saturating_sub(members[slot].voting_weight());
+        members[slot] = Member::new(Pubkey::default(), 0, 0);
+        self.member_count = self.member_count.saturating_sub(1);
+
+        Ok(slot)
+    }
+
+    /// `total_weight` once the member in `slot` is replaced by `updated`.
+    pub fn total_weight_with(&self, members: &[Member], slot: usize, updated: &Member) -> Result<u64, ProgramError> {
+        self.total_weight
+            .saturating_sub(members[slot].voting_weight())
+            .checked_add(updated.voting_weight())
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+
+    /// Whether some member still proposes and some member still executes once the member
+    /// in `slot` is replaced by `updated`. Removals pass an empty member.
+    pub fn keeps_role_holders(members: &[Member], slot: usize, updated: &Member) -> bool {
+        let holds = |role: u8| {
+            members
+                .iter()
+                .enumerate()
+                .map(|(index, member)| if index == slot { updated } else { member })
+                .any(|member| member.key != Pubkey::default() && member.has_role(role))
+        };
+
+        holds(Member::PROPOSER) && holds(Member::EXECUTOR)
+    }
+
+    /// Replaces the member in `slot` with `updated`, keeping `total_weight` in step.
+    pub fn update_member(&mut self, members: &mut [Member], slot: usize, updated: Member) -> ProgramResult {
+        self.total_weight = self.total_weight_with(members, slot, &updated)?;
+        members[slot] = updated;
+
+        Ok(())
+    }
+
+    /// Upgrades an account still in the original layout to the current one in place,
+    /// resizing it at `payer`'s expense.
+    pub fn migrate(account: &AccountInfo, payer: &AccountInfo) -> ProgramResult {
+        let (vault, members) = Self::original(account)?.upgrade();
+
+        Self::write_migrated(account, payer, vault, &members)
+    }
+
+    /// Admin and id of an account still in the original layout, which its address was derived from.
+    pub fn original_seeds(account: &AccountInfo) -> Result<(Pubkey, u64), ProgramError> {
+        let vault = Self::original(account)?;
+
+        Ok((vault.admin, vault.id))
+    }
+
+    fn original(account: &AccountInfo) -> Result<MultiSignatureVaultV0, ProgramError> {
+        if *account.owner() != crate::ID {
+            return Err(ProgramError::InvalidAccountOwner);
+        };
+
+        MultiSignatureVaultV0::read(&account.try_borrow_data()?)
+    }
+
+    /// Writes a migrated header and member list, resizing the account to fit them.
+    fn write_migrated(account: &AccountInfo, payer: &AccountInfo, vault: Self, members: &[Member]) -> ProgramResult {
+        let new_size = Self::space(members.len());
+
+        if new_size > account.data_len() {
+            resize_account(account, payer, new_size)?;
+        };
+
+        {
+            let mut data = account.try_borrow_mut_data()?;
+
+            unsafe { core::ptr::write_unaligned(data.as_mut_ptr() as *mut Self, vault) };
+
+            for (slot, member) in members.iter().enumerate() {
+                unsafe { core::ptr::write_unaligned(data[Self::space(slot)..].as_mut_ptr() as *mut Member, *member) };
+            }
+        }
+
+        resize_account(account, payer, new_size)
+    }
+
+    /// Fails if the multisig account `account` is paused, so no outflow may leave it.
+    pub fn check_not_paused(account: &AccountInfo) -> ProgramResult {
+        if !Self::from_account_info(account)?.is_active {
+            msg!("Vault is paused");
+            return Err(TreasuryError::MultisigInactive.into());
+        };
+
+        Ok(())
+    }
+
+    /// Tags a freshly created account as `MultiSignatureVault` and returns it for initialization.
+    pub fn initialize(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        write_header::<Self>(account, Self::SIZE)?;
+        Self::from_account_info_mut(account)
+    }
+
+    /// Approval weight a `stream_type` proposal needs. Unpausing always needs every voter.
+    pub fn threshold_for(&self, stream_type: &StreamType) -> u64 {
+        if *stream_type == StreamType::Unpause {
+            return self.total_weight;
+        }
+
+        match self.type_thresholds[stream_type.clone() as usize] {
+            0 => self.threshold,
+            threshold => threshold,
+        }
+    }
+
+    pub fn amount_tiers(&self) -> &[AmountTier] {
+        &self.amount_tiers[..(self.amount_tier_count as usize).min(Self::MAX_AMOUNT_TIERS)]
+    }
+
+    /// Threshold of the amount tier an outflow of `amount` tokens of `mint` falls in, if any.
+    pub fn tier_threshold(&self, mint: &Pubkey, amount: u64) -> Option<u64> {
+        self.amount_tiers()
+            .iter()
+            .rev()
+            .find(|tier| tier.mint == *mint && amount >= tier.min_outflow)
+            .map(|tier| tier.threshold)
+    }
+
+    /// Approval weight a proposal performing `action` needs: the threshold for its type,
+    /// raised to that of the amount tier its outflow falls in.
+    pub fn required_threshold(&self, stream_type: &StreamType, action: &ProposalAction) -> Result<u64, ProgramError> {
+        let type_threshold = self.threshold_for(stream_type);
+
+        let Some(mint) = action.mint() else {
+            return Ok(type_threshold);
+        };
+
+        Ok(self.tier_threshold(mint, action.total_outflow()?).map_or(type_threshold, |tier| tier.max(type_threshold)))
+    }
+
+    /// Highest threshold any proposal can need; membership changes must keep it reachable.
+    pub fn max_threshold(&self) -> u64 {
+        self.type_thresholds
+            .iter()
+            .copied()
+            .chain(self.amount_tiers().iter().map(|tier| tier.threshold))
+            .fold(self.threshold, u64::max)
+    }
+
+    /// Whether paying `amount` out of a treasury holding `balance` keeps it at or above `minimum_balance`.
+    pub fn keeps_minimum_balance(&self, balance: u64, amount: u64) -> bool {
+        balance
+            .checked_sub(amount)
+            .is_some_and(|remaining| remaining >= self.minimum_balance)
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn original_bytes(vault: &MultiSignatureVaultV0) -> Vec<u8> {
+        let mut data = unsafe { core::slice::from_raw_parts(vault as *const MultiSignatureVaultV0 as *const u8, core::mem::size_of::<MultiSignatureVaultV0>()) }.to_vec();
+        data.resize(MultiSignatureVaultV0::SPACE, 0);
+        data
+    }
+
+    #[test]
+    fn test_upgrades_original_layout_with_unweighted_members() {
+        let mut old: MultiSignatureVaultV0 = unsafe { core::mem::zeroed() };
+        old.id = 7;
+        old.admin = [9u8; 32];
+        old.is_active = true;
+        old.member_count = 2;
+        old.member_keys[..3].copy_from_slice(&[[1u8; 32], [2u8; 32], [3u8; 32]]);
+        old.threshold = 2;
+        old.treasury_vault = [5u8; 32];
+
+        let (vault, members) = MultiSignatureVaultV0::read(&original_bytes(&old)).unwrap().upgrade();
+
+        assert_eq!((vault.discriminator, vault.version), (MultiSignatureVault::DISCRIMINATOR, MultiSignatureVault::VERSION));
+        assert_eq!((vault.id, vault.admin, vault.creator), (7, [9u8; 32], [9u8; 32]));
+        assert_eq!((vault.member_count, vault.member_slots, vault.total_weight, vault.threshold), (2, 2, 2, 2));
+        assert_eq!(members, vec![Member::new([1u8; 32], 1, Member::ALL_ROLES), Member::new([2u8; 32], 1, Member::ALL_ROLES)]);
+        assert_eq!(vault.treasury_vault, [5u8; 32]);
+        assert!(vault.is_active && !vault.deposit_allowlist);
+        assert_eq!((vault.amount_tier_count, vault.timelock_delay, vault.guardian), (0, 0, Pubkey::default()));
+    }
+
+    #[test]
+    fn test_original_layout_read_rejects_current_and_unknown_accounts() {
+        let mut data = vec![0u8; MultiSignatureVault::SIZE];
+        data[..8].copy_from_slice(&MultiSignatureVault::DISCRIMINATOR);
+        assert_eq!(MultiSignatureVaultV0::read(&data).err(), Some(TreasuryError::AccountUpToDate.into()));
+
+        let data = vec![0u8; MultiSignatureVaultV0::SPACE - 1];
+        assert_eq!(MultiSignatureVaultV0::read(&data).err(), Some(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_keeps_minimum_balance() {
+        let mut vault: MultiSignatureVault = unsafe { core::mem::zeroed() };
+        vault.minimum_balance = 1_000;
+
+        assert!(vault.keeps_minimum_balance(5_000, 4_000));
+        assert!(!vault.keeps_minimum_balance(5_000, 4_001));
+        assert!(!vault.keeps_minimum_balance(500, 600));
+    }
+
+    #[test]
+    fn test_threshold_for_falls_back_to_vault_threshold() {
+        let mut vault: MultiSignatureVault = unsafe { core::mem::zeroed() };
+        vault.threshold = 2;
+        vault.type_thresholds[StreamType::Vesting as usize] = 4;
+
+        assert_eq!(vault.threshold_for(&StreamType::Vesting), 4);
+        assert_eq!(vault.threshold_for(&StreamType::TokenTransfers), 2);
+        assert_eq!(vault.max_threshold(), 4);
+    }
+
+    #[test]
+    fn test_unpause_needs_full_voting_weight() {
+        let mut vault: MultiSignatureVault = unsafe { core::mem::zeroed() };
+        vault.threshold = 2;
+        vault.total_weight = 5;
+        vault.type_thresholds[StreamType::Unpause as usize] = 3;
+
+        assert_eq!(vault.threshold_for(&StreamType::Unpause), 5);
+    }
+
+    #[test]
+    fn test_tier_threshold_picks_highest_tier_reached() {
+        let mut vault: MultiSignatureVault = unsafe { core::mem::zeroed() };
+        vault.threshold = 2;
+        vault.amount_tier_count = 3;
+        vault.amount_tiers[0] = AmountTier { mint: [1u8; 32], min_outflow: 1_000, threshold: 3 };
+        vault.amount_tiers[1] = AmountTier { mint: [1u8; 32], min_outflow: 10_000, threshold: 5 };
+        vault.amount_tiers[2] = AmountTier { mint: [2u8; 32], min_outflow: 0, threshold: 4 };
+
+        assert_eq!(vault.tier_threshold(&[1u8; 32], 999), None);
+        assert_eq!(vault.tier_threshold(&[1u8; 32], 1_000), Some(3));
+        assert_eq!(vault.tier_threshold(&[1u8; 32], 50_000), Some(5));
+        assert_eq!(vault.tier_threshold(&[2u8; 32], 1), Some(4));
+        assert_eq!(vault.tier_threshold(&[3u8; 32], 50_000), None);
+        assert_eq!(vault.max_threshold(), 5);
+    }
+
+    #[test]
+    fn test_role_update_must_keep_a_proposer_and_an_executor() {
+        let members = [
+            Member::new([1u8; 32], 1, Member::PROPOSER | Member::VOTER),
+            Member::new([2u8; 32], 1, Member::EXECUTOR | Member::VOTER),
+            Member::new([3u8; 32], 1, Member::VOTER),
+        ];
+
+        assert!(MultiSignatureVault::keeps_role_holders(&members, 2, &Member::new([3u8; 32], 1, Member::ALL_ROLES)));
+        assert!(!MultiSignatureVault::keeps_role_holders(&members, 0, &Member::new([1u8; 32], 1, Member::VOTER)));
+        assert!(!MultiSignatureVault::keeps_role_holders(&members, 1, &Member::new([2u8; 32], 1, Member::VOTER)));
+        assert!(MultiSignatureVault::keeps_role_holders(&members, 2, &Member::new([3u8; 32], 1, Member::VOTER | Member::EXECUTOR)));
+    }
+
+    #[test]
+    fn test_removal_must_keep_a_proposer_and_an_executor() {
+        let removed = Member::new(Pubkey::default(), 0, 0);
+        let members = [
+            Member::new([1u8; 32], 1, Member::ALL_ROLES),
+            Member::new([2u8; 32], 1, Member::EXECUTOR | Member::VOTER),
+            Member::new(Pubkey::default(), 0, 0),
+            Member::new([4u8; 32], 1, Member::PROPOSER),
+        ];
+
+        assert!(MultiSignatureVault::keeps_role_holders(&members, 1, &removed));
+        assert!(MultiSignatureVault::keeps_role_holders(&members, 3, &removed));
+        assert!(!MultiSignatureVault::keeps_role_holders(&members[..3], 0, &removed));
+        assert!(!MultiSignatureVault::keeps_role_holders(&members[1..], 0, &removed));
+    }
+
+    #[test]
+    fn test_size_matches_layout() {
+        assert_eq!(MultiSignatureVault::SIZE, core::mem::size_of::<MultiSignatureVault>());
+    }
+
+    #[test]
+    fn test_removed_member_slot_is_not_reused() {
+        let mut vault: MultiSignatureVault = unsafe { core::mem::zeroed() };
+        let mut members = [
+            Member::new([1u8; 32], 1, Member::ALL_ROLES),
+            Member::new([2u8; 32], 3, Member::ALL_ROLES),
+            Member::new([3u8; 32], 1, Member::ALL_ROLES),
+        ];
+        vault.member_count = 3;
+        vault.member_slots = 3;
+        vault.total_weight = 5;
+
+        assert_eq!(vault.remove_member(&mut members, &[2u8; 32]).unwrap(), 1);
+        assert!(members[1].is_removed());
+        assert_eq!(members[1].weight, 0);
+        assert_eq!(vault.member_count, 2);
+        assert_eq!(vault.total_weight, 2);
+
+        assert_eq!(MultiSignatureVault::member_slot(&members, &[3u8; 32]), Some(2));
+        assert!(!MultiSignatureVault::is_member(&members, &[2u8; 32]));
+        assert!(!MultiSignatureVault::is_member(&members, &Pubkey::default()));
+        assert!(vault.remove_member(&mut members, &[2u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_update_member_updates_total_weight() {
+        let mut vault: MultiSignatureVault = unsafe { core::mem::zeroed() };
+        let mut members = [Member::new([1u8; 32], 1, Member::ALL_ROLES), Member::new([2u8; 32], 1, Member::ALL_ROLES)];
+        vault.total_weight = 2;
+
+        vault.update_member(&mut members, 1, Member::new([2u8; 32], 5, Member::ALL_ROLES)).unwrap();
+        assert_eq!(members[1].weight, 5);
+        assert_eq!(vault.total_weight, 6);
+    }
+
+    #[test]
+    fn test_only_voters_count_toward_total_weight() {
+        let mut vault: MultiSignatureVault = unsafe { core::mem::zeroed() };
+        let mut members = [
+            Member::new([1u8; 32], 2, Member::ALL_ROLES),
+            Member::new([2u8; 32], 4, Member::PROPOSER),
+        ];
+        vault.total_weight = members.iter().map(Member::voting_weight).sum();
+        assert_eq!(vault.total_weight, 2);
+
+        vault.update_member(&mut members, 1, Member::new([2u8; 32], 4, Member::VOTER)).unwrap();
+        assert_eq!(vault.total_weight, 6);
+        assert!(!members[1].has_role(Member::PROPOSER));
+
+        vault.update_member(&mut members, 0, Member::new([1u8; 32], 2, Member::EXECUTOR)).unwrap();
+        assert_eq!(vault.total_weight, 4);
+        assert_eq!(members[0].weight, 2);
+    }
+}
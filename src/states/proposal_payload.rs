@@ -0,0 +1,786 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+use crate::states::{AmountTier, Member, MultiSignatureVault, StreamType, MAX_RECIPIENTS};
+
+/// Action parameters a proposal is bound to. Serialized into the proposal account at
+/// creation so that execution can only ever perform what the members voted on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProposalAction {
+    BatchPayments(Box<BatchPaymentPayload>),
+    TokenTransfers(TokenTransferPayload),
+    PaymentStreaming(StreamPaymentPayload),
+    Vesting(VestingPayload),
+    RemoveMember(Pubkey),
+    ConfigChange(Box<ConfigChangePayload>),
+    MigrateAccount(Pubkey),
+    SetMemberWeight(MemberWeightPayload),
+    SetMemberRoles(MemberRolesPayload),
+    Unpause,
+    SetGuardian(GuardianPayload),
+    AddMember(NewMemberPayload),
+    /// New admin, who must accept the role; the default key renounces it.
+    TransferAdmin(Pubkey),
+    /// Whether only allowed depositors may fund the treasury.
+    SetDepositAllowlist(bool),
+    AllowDepositor(DepositorPayload),
+    /// Recipient of the treasury-funded stream to cancel.
+    CancelStream(Pubkey),
+}
+
+impl ProposalAction {
+    pub fn try_from_payload(stream_type: &StreamType, data: &[u8]) -> Result<Self, ProgramError> {
+        match stream_type {
+            StreamType::BatchPayments => Ok(Self::BatchPayments(Box::new(BatchPaymentPayload::try_from_bytes(data)?))),
+            StreamType::TokenTransfers => Ok(Self::TokenTransfers(TokenTransferPayload::try_from_bytes(data)?)),
+            StreamType::PaymentStreaming => Ok(Self::PaymentStreaming(StreamPaymentPayload::try_from_bytes(data)?)),
+            StreamType::Vesting => Ok(Self::Vesting(VestingPayload::try_from_bytes(data)?)),
+            StreamType::RemoveMember if data.len() == 32 => Ok(Self::RemoveMember(read_pubkey(data, 0)?)),
+            StreamType::RemoveMember => Err(ProgramError::InvalidInstructionData),
+            StreamType::ConfigChange => Ok(Self::ConfigChange(Box::new(ConfigChangePayload::try_from_bytes(data)?))),
+            StreamType::MigrateAccount if data.len() == 32 => Ok(Self::MigrateAccount(read_pubkey(data, 0)?)),
+            StreamType::MigrateAccount => Err(ProgramError::InvalidInstructionData),
+            StreamType::SetMemberWeight => Ok(Self::SetMemberWeight(MemberWeightPayload::try_from_bytes(data)?)),
+            StreamType::SetMemberRoles => Ok(Self::SetMemberRoles(MemberRolesPayload::try_from_bytes(data)?)),
+            StreamType::Unpause if data.is_empty() => Ok(Self::Unpause),
+            StreamType::Unpause => Err(ProgramError::InvalidInstructionData),
+            StreamType::SetGuardian => Ok(Self::SetGuardian(GuardianPayload::try_from_bytes(data)?)),
+            StreamType::AddMember => Ok(Self::AddMember(NewMemberPayload::try_from_bytes(data)?)),
+            StreamType::TransferAdmin if data.len() == 32 => Ok(Self::TransferAdmin(read_pubkey(data, 0)?)),
+            StreamType::TransferAdmin => Err(ProgramError::InvalidInstructionData),
+            StreamType::SetDepositAllowlist if data.len() == 1 => Ok(Self::SetDepositAllowlist(read_bool(data, 0)?)),
+            StreamType::SetDepositAllowlist => Err(ProgramError::InvalidInstructionData),
+            StreamType::AllowDepositor => Ok(Self::AllowDepositor(DepositorPayload::try_from_bytes(data)?)),
+            StreamType::CancelStream if data.len() == 32 => Ok(Self::CancelStream(read_pubkey(data, 0)?)),
+            StreamType::CancelStream => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+
+    /// Mint the action moves tokens of, if any.
+    pub fn mint(&self) -> Option<&Pubkey> {
+        match self {
+            Self::BatchPayments(payload) => Some(&payload.mint),
+            Self::TokenTransfers(payload) => Some(&payload.mint),
+            Self::PaymentStreaming(payload) => Some(&payload.mint),
+            Self::Vesting(payload) => Some(&payload.mint),
+            Self::RemoveMember(_) | Self::ConfigChange(_) | Self::MigrateAccount(_) | Self::SetMemberWeight(_) | Self::SetMemberRoles(_)
+                | Self::Unpause | Self::SetGuardian(_) | Self::AddMember(_) | Self::TransferAdmin(_)
+                | Self::SetDepositAllowlist(_) | Self::AllowDepositor(_) | Self::CancelStream(_) => None,
+        }
+    }
+
+    /// Total number of tokens the action pays out.
+    pub fn total_outflow(&self) -> Result<u64, ProgramError> {
+        match self {
+            Self::BatchPayments(payload) => payload.total_amount(),
+            Self::TokenTransfers(payload) => Ok(payload.amount),
+            Self::PaymentStreaming(payload) => Ok(payload.total_amount),
+            Self::Vesting(payload) => Ok(payload.amount),
+            Self::RemoveMember(_) | Self::ConfigChange(_) | Self::MigrateAccount(_) | Self::SetMemberWeight(_) | Self::SetMemberRoles(_)
+                | Self::Unpause | Self::SetGuardian(_) | Self::AddMember(_) | Self::TransferAdmin(_)
+                | Self::SetDepositAllowlist(_) | Self::AllowDepositor(_) | Self::CancelStream(_) => Ok(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenTransferPayload {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+impl TokenTransferPayload {
+    pub const LEN: usize = 72;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let payload = Self {
+            mint: read_pubkey(data, 0)?,
+            recipient: read_pubkey(data, 32)?,
+            amount: read_u64(data, 64)?,
+        };
+
+        if payload.amount == 0 || payload.recipient == Pubkey::default() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(payload)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchPaymentPayload {
+    pub mint: Pubkey,
+    pub batch_id: u64,
+    pub amount_per_recipient: u64,
+    pub num_recipients: u8,
+    pub recipients: [Pubkey; MAX_RECIPIENTS],
+}
+
+impl BatchPaymentPayload {
+    pub const HEADER_LEN: usize = 49;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::HEADER_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let num_recipients = data[48];
+        if num_recipients == 0
+            || num_recipients as usize > MAX_RECIPIENTS
+            || data.len() != Self::HEADER_LEN + num_recipients as usize * 32
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut recipients = [Pubkey::default(); MAX_RECIPIENTS];
+        for (i, recipient) in recipients.iter_mut().take(num_recipients as usize).enumerate() {
+            *recipient = read_pubkey(data, Self::HEADER_LEN + i * 32)?;
+
+            if *recipient == Pubkey::default() {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        }
+
+        let payload = Self {
+            mint: read_pubkey(data, 0)?,
+            batch_id: read_u64(data, 32)?,
+            amount_per_recipient: read_u64(data, 40)?,
+            num_recipients,
+            recipients,
+        };
+
+        if payload.amount_per_recipient == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        payload.total_amount()?;
+
+        Ok(payload)
+    }
+
+    pub fn get_active_recipients(&self) -> &[Pubkey] {
+        &self.recipients[..self.num_recipients as usize]
+    }
+
+    pub fn total_amount(&self) -> Result<u64, ProgramError> {
+        self.amount_per_recipient
+            .checked_mul(self.num_recipients as u64)
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamPaymentPayload {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+impl StreamPaymentPayload {
+    pub const LEN: usize = 88;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let payload = Self {
+            mint: read_pubkey(data, 0)?,
+            recipient: read_pubkey(data, 32)?,
+            total_amount: read_u64(data, 64)?,
+            start_time: read_i64(data, 72)?,
+            end_time: read_i64(data, 80)?,
+        };
+
+        if payload.total_amount == 0 || payload.start_time >= payload.end_time {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(payload)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VestingPayload {
+    pub mint: Pubkey,
+    pub company_name: [u8; 32],
+    pub beneficiary: Pubkey,
+    pub cliff_period: i64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub amount: u64,
+}
+
+impl VestingPayload {
+    pub const LEN: usize = 128;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let payload = Self {
+            mint: read_pubkey(data, 0)?,
+            company_name: read_pubkey(data, 32)?,
+            beneficiary: read_pubkey(data, 64)?,
+            cliff_period: read_i64(data, 96)?,
+            start_time: read_i64(data, 104)?,
+            end_time: read_i64(data, 112)?,
+            amount: read_u64(data, 120)?,
+        };
+
+        if payload.amount == 0 || payload.cliff_period < 0 || payload.start_time >= payload.end_time {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(payload)
+    }
+
+    /// Instruction data expected by `process_init_beneficiary`.
+    pub fn beneficiary_args(&self) -> [u8; 64] {
+        let mut args = [0u8; 64];
+        args[0..32].copy_from_slice(&self.company_name);
+        args[32..40].copy_from_slice(&self.cliff_period.to_le_bytes());
+        args[40..48].copy_from_slice(&self.start_time.to_le_bytes());
+        args[48..56].copy_from_slice(&self.end_time.to_le_bytes());
+        args[56..64].copy_from_slice(&self.amount.to_le_bytes());
+        args
+    }
+}
+
+/// Threshold override for one `StreamType`; a zero threshold clears the override.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TypeThreshold {
+    pub stream_type: u8,
+    pub threshold: u64,
+}
+
+/// New vault settings. The fixed fields may be followed by `count: u8` and `count`
+/// `(stream_type: u8, threshold: u64)` overrides; types not listed keep their threshold.
+/// Those may in turn be followed by `count: u8` and `count` `(mint, min_outflow: u64,
+/// threshold: u64)` amount tiers, which replace the vault's whole tier table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfigChangePayload {
+    pub threshold: u64,
+    pub proposal_expiry: i64,
+    pub minimum_balance: u64,
+    pub timelock_delay: i64,
+    pub num_type_thresholds: u8,
+    pub type_thresholds: [TypeThreshold; StreamType::MAX_TYPES],
+    pub replaces_amount_tiers: bool,
+    pub num_amount_tiers: u8,
+    pub amount_tiers: [AmountTier; MultiSignatureVault::MAX_AMOUNT_TIERS],
+}
+
+impl ConfigChangePayload {
+    pub const LEN: usize = 32;
+    pub const TYPE_THRESHOLD_LEN: usize = 9;
+    pub const AMOUNT_TIER_LEN: usize = 48;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut payload = Self {
+            threshold: read_u64(data, 0)?,
+            proposal_expiry: read_i64(data, 8)?,
+            minimum_balance: read_u64(data, 16)?,
+            timelock_delay: read_i64(data, 24)?,
+            num_type_thresholds: 0,
+            type_thresholds: [TypeThreshold::default(); StreamType::MAX_TYPES],
+            replaces_amount_tiers: false,
+            num_amount_tiers: 0,
+            amount_tiers: [AmountTier::default(); MultiSignatureVault::MAX_AMOUNT_TIERS],
+        };
+
+        if data.len() == Self::LEN {
+            return Ok(payload);
+        }
+
+        let count = data[Self::LEN] as usize;
+        let tiers_offset = Self::LEN + 1 + count * Self::TYPE_THRESHOLD_LEN;
+        if count > StreamType::MAX_TYPES || data.len() < tiers_offset {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        for i in 0..count {
+            let offset = Self::LEN + 1 + i * Self::TYPE_THRESHOLD_LEN;
+            let stream_type = data[offset];
+            StreamType::try_from(&stream_type)?;
+
+            if payload.get_type_thresholds().iter().any(|entry| entry.stream_type == stream_type) {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            payload.type_thresholds[i] = TypeThreshold { stream_type, threshold: read_u64(data, offset + 1)? };
+            payload.num_type_thresholds += 1;
+        }
+
+        if data.len() == tiers_offset {
+            return Ok(payload);
+        }
+
+        let count = data[tiers_offset] as usize;
+        if count > MultiSignatureVault::MAX_AMOUNT_TIERS
+            || data.len() != tiers_offset + 1 + count * Self::AMOUNT_TIER_LEN
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        for i in 0..count {
+            let offset = tiers_offset + 1 + i * Self::AMOUNT_TIER_LEN;
+            let tier = AmountTier {
+                mint: read_pubkey(data, offset)?,
+                min_outflow: read_u64(data, offset + 32)?,
+                threshold: read_u64(data, offset + 40)?,
+            };
+
+            // Tiers of a mint must be listed in ascending `min_outflow` order, and a larger
+            // outflow never needs less approval than a smaller one.
+            let out_of_order = payload.get_amount_tiers()
+                .iter()
+                .any(|entry| entry.mint == tier.mint && (entry.min_outflow >= tier.min_outflow || entry.threshold > tier.threshold));
+
+            if tier.mint == Pubkey::default() || out_of_order {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+
+            payload.amount_tiers[i] = tier;
+            payload.num_amount_tiers += 1;
+        }
+
+        payload.replaces_amount_tiers = true;
+
+        Ok(payload)
+    }
+
+    pub fn get_amount_tiers(&self) -> &[AmountTier] {
+        &self.amount_tiers[..self.num_amount_tiers as usize]
+    }
+
+    pub fn get_type_thresholds(&self) -> &[TypeThreshold] {
+        &self.type_thresholds[..self.num_type_thresholds as usize]
+    }
+
+    /// Thresholds are in voting weight units and must be reachable with `total_weight`.
+    pub fn validate(&self, total_weight: u64) -> ProgramResult {
+        if self.threshold == 0 || self.threshold > total_weight {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.get_type_thresholds().iter().any(|entry| entry.threshold > total_weight) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.get_amount_tiers().iter().any(|tier| tier.threshold == 0 || tier.threshold > total_weight) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if self.proposal_expiry <= 0 || self.timelock_delay < 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // A timelock as long as the voting window would outlast every proposal's vote.
+        if self.timelock_delay >= self.proposal_expiry {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemberWeightPayload {
+    pub member: Pubkey,
+    pub weight: u64,
+}
+
+impl MemberWeightPayload {
+    pub const LEN: usize = 40;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let payload = Self {
+            member: read_pubkey(data, 0)?,
+            weight: read_u64(data, 32)?,
+        };
+
+        if payload.weight == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(payload)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemberRolesPayload {
+    pub member: Pubkey,
+    pub roles: u8,
+}
+
+impl MemberRolesPayload {
+    pub const LEN: usize = 33;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let payload = Self {
+            member: read_pubkey(data, 0)?,
+            roles: data[32],
+        };
+
+        if payload.roles & !Member::ALL_ROLES != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(payload)
+    }
+}
+
+/// Member to add by proposal, for vaults without an admin or that prefer to govern membership.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NewMemberPayload {
+    pub member: Pubkey,
+    pub weight: u64,
+    pub roles: u8,
+}
+
+impl NewMemberPayload {
+    pub const LEN: usize = 41;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let payload = Self {
+            member: read_pubkey(data, 0)?,
+            weight: read_u64(data, 32)?,
+            roles: data[40],
+        };
+
+        if payload.member == Pubkey::default() || payload.weight == 0 || payload.roles & !Member::ALL_ROLES != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(payload)
+    }
+}
+
+/// Who may pause the vault: `guardian` alone (the default key for none), or signing members
+/// holding `pause_threshold` voting weight (0 disables member pauses).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuardianPayload {
+    pub guardian: Pubkey,
+    pub pause_threshold: u64,
+}
+
+impl GuardianPayload {
+    pub const LEN: usize = 40;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            guardian: read_pubkey(data, 0)?,
+            pause_threshold: read_u64(data, 32)?,
+        })
+    }
+}
+
+/// Allows or disallows `depositor` to fund the treasury while the deposit allowlist is on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepositorPayload {
+    pub depositor: Pubkey,
+    pub allowed: bool,
+}
+
+impl DepositorPayload {
+    pub const LEN: usize = 33;
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let payload = Self {
+            depositor: read_pubkey(data, 0)?,
+            allowed: read_bool(data, 32)?,
+        };
+
+        if payload.depositor == Pubkey::default() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(payload)
+    }
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey, ProgramError> {
+    data.get(offset..offset + 32)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, ProgramError> {
+    data.get(offset..offset + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64, ProgramError> {
+    data.get(offset..offset + 8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(i64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn read_bool(data: &[u8], offset: usize) -> Result<bool, ProgramError> {
+    match data.get(offset) {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_config_change_payload_roundtrip_and_validation() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.extend_from_slice(&86400i64.to_le_bytes());
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.extend_from_slice(&3600i64.to_le_bytes());
+
+        let config = ConfigChangePayload::try_from_bytes(&data).unwrap();
+        assert_eq!(config.threshold, 3);
+        assert_eq!(config.proposal_expiry, 86400);
+        assert_eq!(config.minimum_balance, 1_000_000);
+        assert_eq!(config.timelock_delay, 3600);
+
+        assert!(config.get_type_thresholds().is_empty());
+
+        assert!(config.validate(3).is_ok());
+        assert!(config.validate(2).is_err());
+        assert!(ConfigChangePayload::try_from_bytes(&data[..24]).is_err());
+
+        data[24..32].copy_from_slice(&(-1i64).to_le_bytes());
+        assert!(ConfigChangePayload::try_from_bytes(&data).unwrap().validate(3).is_err());
+
+        data[24..32].copy_from_slice(&86400i64.to_le_bytes());
+        assert!(ConfigChangePayload::try_from_bytes(&data).unwrap().validate(3).is_err());
+    }
+
+    #[test]
+    fn test_config_change_payload_type_thresholds() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.extend_from_slice(&86400i64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.push(1);
+        data.push(StreamType::Vesting as u8);
+        data.extend_from_slice(&4u64.to_le_bytes());
+
+        let config = ConfigChangePayload::try_from_bytes(&data).unwrap();
+        assert_eq!(config.get_type_thresholds(), &[TypeThreshold { stream_type: StreamType::Vesting as u8, threshold: 4 }]);
+        assert!(config.validate(4).is_ok());
+        assert!(config.validate(3).is_err());
+
+        let mut duplicated = data.clone();
+        duplicated[32] = 2;
+        duplicated.extend_from_slice(&data[33..]);
+        assert!(ConfigChangePayload::try_from_bytes(&duplicated).is_err());
+
+        data[33] = 200;
+        assert!(ConfigChangePayload::try_from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn test_config_change_payload_amount_tiers() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.extend_from_slice(&86400i64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.push(0);
+        data.push(2);
+        for (min_outflow, threshold) in [(1_000u64, 3u64), (10_000, 4)] {
+            data.extend_from_slice(&[1u8; 32]);
+            data.extend_from_slice(&min_outflow.to_le_bytes());
+            data.extend_from_slice(&threshold.to_le_bytes());
+        }
+
+        let config = ConfigChangePayload::try_from_bytes(&data).unwrap();
+        assert!(config.replaces_amount_tiers);
+        assert!(config.get_type_thresholds().is_empty());
+        assert_eq!(config.get_amount_tiers()[1], AmountTier { mint: [1u8; 32], min_outflow: 10_000, threshold: 4 });
+        assert!(config.validate(4).is_ok());
+        assert!(config.validate(3).is_err());
+
+        let mut unordered = data.clone();
+        unordered[34 + 48 + 32..34 + 48 + 40].copy_from_slice(&500u64.to_le_bytes());
+        assert!(ConfigChangePayload::try_from_bytes(&unordered).is_err());
+
+        let cleared = ConfigChangePayload::try_from_bytes(&[&data[..33], &[0]].concat()).unwrap();
+        assert!(cleared.replaces_amount_tiers);
+        assert!(cleared.get_amount_tiers().is_empty());
+    }
+
+    #[test]
+    fn test_config_change_payload_rejects_decreasing_tier_thresholds() {
+        let tiers = |tiers: &[([u8; 32], u64, u64)]| {
+            let mut data = Vec::new();
+            data.extend_from_slice(&2u64.to_le_bytes());
+            data.extend_from_slice(&86400i64.to_le_bytes());
+            data.extend_from_slice(&0u64.to_le_bytes());
+            data.extend_from_slice(&0i64.to_le_bytes());
+            data.push(0);
+            data.push(tiers.len() as u8);
+            for (mint, min_outflow, threshold) in tiers {
+                data.extend_from_slice(mint);
+                data.extend_from_slice(&min_outflow.to_le_bytes());
+                data.extend_from_slice(&threshold.to_le_bytes());
+            }
+            ConfigChangePayload::try_from_bytes(&data)
+        };
+
+        assert!(tiers(&[([1u8; 32], 1_000, 4), ([1u8; 32], 10_000, 2)]).is_err());
+        assert!(tiers(&[([1u8; 32], 1_000, 3), ([1u8; 32], 10_000, 3)]).is_ok());
+        assert!(tiers(&[([1u8; 32], 1_000, 4), ([2u8; 32], 10_000, 2)]).is_ok());
+    }
+
+    #[test]
+    fn test_batch_payment_payload_requires_exact_recipient_list() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[9u8; 32]);
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.push(2);
+        data.extend_from_slice(&[3u8; 32]);
+        data.extend_from_slice(&[4u8; 32]);
+
+        let action = ProposalAction::try_from_payload(&StreamType::BatchPayments, &data).unwrap();
+        assert_eq!(action.mint(), Some(&[9u8; 32]));
+        assert_eq!(action.total_outflow().unwrap(), 1000);
+
+        let ProposalAction::BatchPayments(payload) = action else { panic!("expected batch payload") };
+        assert_eq!(payload.get_active_recipients(), &[[3u8; 32], [4u8; 32]]);
+
+        assert!(BatchPaymentPayload::try_from_bytes(&data[..data.len() - 32]).is_err());
+    }
+
+    #[test]
+    fn test_member_weight_payload_requires_positive_weight() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[5u8; 32]);
+        data.extend_from_slice(&3u64.to_le_bytes());
+
+        let action = ProposalAction::try_from_payload(&StreamType::SetMemberWeight, &data).unwrap();
+        assert_eq!(action, ProposalAction::SetMemberWeight(MemberWeightPayload { member: [5u8; 32], weight: 3 }));
+        assert_eq!(action.mint(), None);
+
+        data[32..].copy_from_slice(&0u64.to_le_bytes());
+        assert!(MemberWeightPayload::try_from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn test_member_roles_payload_rejects_unknown_flags() {
+        let mut data = vec![5u8; 32];
+        data.push(Member::VOTER | Member::EXECUTOR);
+
+        let payload = MemberRolesPayload::try_from_bytes(&data).unwrap();
+        assert_eq!(payload.roles, Member::VOTER | Member::EXECUTOR);
+
+        data[32] = 1 << 7;
+        assert!(MemberRolesPayload::try_from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn test_pause_payloads() {
+        assert_eq!(ProposalAction::try_from_payload(&StreamType::Unpause, &[]), Ok(ProposalAction::Unpause));
+        assert!(ProposalAction::try_from_payload(&StreamType::Unpause, &[0]).is_err());
+
+        let mut data = [7u8; 32].to_vec();
+        data.extend_from_slice(&2u64.to_le_bytes());
+
+        let action = ProposalAction::try_from_payload(&StreamType::SetGuardian, &data).unwrap();
+        assert_eq!(action, ProposalAction::SetGuardian(GuardianPayload { guardian: [7u8; 32], pause_threshold: 2 }));
+        assert!(action.mint().is_none());
+        assert!(GuardianPayload::try_from_bytes(&data[..39]).is_err());
+    }
+
+    #[test]
+    fn test_remove_member_payload_is_exactly_one_key() {
+        assert_eq!(ProposalAction::try_from_payload(&StreamType::RemoveMember, &[7u8; 32]), Ok(ProposalAction::RemoveMember([7u8; 32])));
+        assert!(ProposalAction::try_from_payload(&StreamType::RemoveMember, &[7u8; 31]).is_err());
+        assert!(ProposalAction::try_from_payload(&StreamType::RemoveMember, &[7u8; 33]).is_err());
+    }
+
+    #[test]
+    fn test_migrate_account_payload_is_exactly_one_key() {
+        assert_eq!(ProposalAction::try_from_payload(&StreamType::MigrateAccount, &[7u8; 32]), Ok(ProposalAction::MigrateAccount([7u8; 32])));
+        assert!(ProposalAction::try_from_payload(&StreamType::MigrateAccount, &[7u8; 31]).is_err());
+        assert!(ProposalAction::try_from_payload(&StreamType::MigrateAccount, &[7u8; 33]).is_err());
+    }
+
+    #[test]
+    fn test_admin_payloads() {
+        assert_eq!(ProposalAction::try_from_payload(&StreamType::TransferAdmin, &[0u8; 32]), Ok(ProposalAction::TransferAdmin(Pubkey::default())));
+        assert!(ProposalAction::try_from_payload(&StreamType::TransferAdmin, &[0u8; 31]).is_err());
+
+        let mut data = [7u8; 32].to_vec();
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.push(Member::VOTER);
+
+        let action = ProposalAction::try_from_payload(&StreamType::AddMember, &data).unwrap();
+        assert_eq!(action, ProposalAction::AddMember(NewMemberPayload { member: [7u8; 32], weight: 3, roles: Member::VOTER }));
+        assert_eq!(action.total_outflow(), Ok(0));
+
+        data[32..40].copy_from_slice(&0u64.to_le_bytes());
+        assert!(NewMemberPayload::try_from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn test_deposit_allowlist_payloads() {
+        assert_eq!(ProposalAction::try_from_payload(&StreamType::SetDepositAllowlist, &[1]), Ok(ProposalAction::SetDepositAllowlist(true)));
+        assert!(ProposalAction::try_from_payload(&StreamType::SetDepositAllowlist, &[2]).is_err());
+        assert!(ProposalAction::try_from_payload(&StreamType::SetDepositAllowlist, &[0, 0]).is_err());
+
+        let mut data = [7u8; 32].to_vec();
+        data.push(1);
+
+        let action = ProposalAction::try_from_payload(&StreamType::AllowDepositor, &data).unwrap();
+        assert_eq!(action, ProposalAction::AllowDepositor(DepositorPayload { depositor: [7u8; 32], allowed: true }));
+        assert!(action.mint().is_none());
+        assert!(DepositorPayload::try_from_bytes(&[&[0u8; 32][..], &[1]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_cancel_stream_payload() {
+        let action = ProposalAction::try_from_payload(&StreamType::CancelStream, &[7u8; 32]).unwrap();
+        assert_eq!(action, ProposalAction::CancelStream([7u8; 32]));
+        assert!(action.mint().is_none());
+        assert!(ProposalAction::try_from_payload(&StreamType::CancelStream, &[7u8; 33]).is_err());
+        assert_eq!(StreamType::CancelStream.proposer_role(), Member::TREASURER);
+    }
+}
@@ -1,8 +1,13 @@
 use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError, pubkey::Pubkey, *};
 use shank::ShankAccount;
 
+use crate::states::{check_account, write_header, AccountHeader};
+
 #[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+#[repr(C)]
 pub struct PaymentStreamingInfo {
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub sender: Pubkey,
     pub recipient: Pubkey,
     pub sender_token_account: Pubkey,
@@ -24,13 +29,16 @@ pub struct PaymentStreamingInfo {
 // -> Withdraw From Stream (done)
 // -> Cancel Stream  
 
+impl AccountHeader for PaymentStreamingInfo {
+    const DISCRIMINATOR: [u8; 8] = [232, 188, 126, 251, 166, 252, 96, 167];
+    const VERSION: u8 = 1;
+}
+
 impl PaymentStreamingInfo {
     pub const SIZE: usize = core::mem::size_of::<PaymentStreamingInfo>();
 
     pub fn from_account_info(account: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
             &*(data.as_ptr() as *const Self)
@@ -38,12 +46,16 @@ impl PaymentStreamingInfo {
     }
 
     pub fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(RefMut::map(account.try_borrow_mut_data()?, |data| unsafe {
             &mut *(data.as_mut_ptr() as *mut Self)
         }))
     }
+
+    /// Tags a freshly created account as `PaymentStreamingInfo` and returns it for initialization.
+    pub fn initialize(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        write_header::<Self>(account, Self::SIZE)?;
+        Self::from_account_info_mut(account)
+    }
 }
\ No newline at end of file
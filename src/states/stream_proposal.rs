@@ -1,8 +1,21 @@
 use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError, pubkey::Pubkey, ProgramResult};
 use shank::ShankAccount;
 
+use crate::{error::TreasuryError, states::{check_account, resize_account, write_header, AccountHeader, Member}};
+
+/// Proposal `n` of a multisig lives at `pda::find_stream_proposal(multisig account, n)`, where `n`
+/// counts up from zero with the vault's `total_proposals`. Its action payload follows the
+/// struct, then two vote bitmaps (approvals, rejections) of `vote_bitmap_len` bytes each,
+/// indexed by member slot. The bitmaps cover
+/// the slots that existed when the proposal was created. Vote tallies and
+/// `required_threshold` are in voting weight units. An approved proposal can execute once
+/// `executable_after` has passed and until its `execution_deadline`; until then members may
+/// still vote, and it is rejected once rejections match its approvals.
 #[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+#[repr(C)]
 pub struct StreamProposal {
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub proposer: Pubkey,
     pub stream_name: [u8; 32],           
     pub stream_description: [u8; 128],   
@@ -11,22 +24,168 @@ pub struct StreamProposal {
     pub stream_type: u8,
     pub created_at: i64,
     pub voting_deadline: i64,
-    pub approvals: [Pubkey; 10],         
-    pub approval_count: u8,
-    pub rejections: [Pubkey; 10],        
-    pub rejection_count: u8,
+    pub executable_after: i64,           // Set when the proposal is approved, from the vault timelock
+    pub executed_by: Pubkey,
+    pub executed_at: i64,
+    pub approval_weight: u64,            // Weight of the approving members, as of the last tally
+    pub rejection_weight: u64,           // Weight of the rejecting members, as of the last tally
     pub total_vote_count: u64,
-    pub required_threshold: u8,
+    pub required_threshold: u64,         // Vault threshold for this type and amount, as of the last tally
+    pub status: u8,
+    pub allow_below_minimum: bool,       // Emergency: payout may take the treasury below minimum_balance
+    pub payload_len: u16,                // Action parameters stored right after the struct
+    pub vote_bitmap_len: u16,            // Bytes per vote bitmap stored after the payload
+}
+
+/// Outcome of a proposal, emitted as program data when its account is closed so the history
+/// outlives the account. `executed_by` is the default key and `executed_at` zero unless executed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProposalRecord {
+    pub multisig_id: u64,
+    pub proposal_id: u64,
+    pub stream_type: u8,
     pub status: u8,
+    pub approval_weight: u64,
+    pub rejection_weight: u64,
+    pub total_vote_count: u64,
+    pub proposer: Pubkey,
+    pub executed_by: Pubkey,
+    pub created_at: i64,
+    pub executed_at: i64,
+    pub closed_at: i64,
+}
+
+impl ProposalRecord {
+    pub const LEN: usize = 130;
+
+    pub fn new(proposal: &StreamProposal, closed_at: i64) -> Self {
+        Self {
+            multisig_id: proposal.multisig_id,
+            proposal_id: proposal.proposal_id,
+            stream_type: proposal.stream_type,
+            status: proposal.status,
+            approval_weight: proposal.approval_weight,
+            rejection_weight: proposal.rejection_weight,
+            total_vote_count: proposal.total_vote_count,
+            proposer: proposal.proposer,
+            executed_by: proposal.executed_by,
+            created_at: proposal.created_at,
+            executed_at: proposal.executed_at,
+            closed_at,
+        }
+    }
+
+    /// Fields in declaration order, little-endian and unpadded.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut data = [0u8; Self::LEN];
+        data[0..8].copy_from_slice(&self.multisig_id.to_le_bytes());
+        data[8..16].copy_from_slice(&self.proposal_id.to_le_bytes());
+        data[16] = self.stream_type;
+        data[17] = self.status;
+        data[18..26].copy_from_slice(&self.approval_weight.to_le_bytes());
+        data[26..34].copy_from_slice(&self.rejection_weight.to_le_bytes());
+        data[34..42].copy_from_slice(&self.total_vote_count.to_le_bytes());
+        data[42..74].copy_from_slice(&self.proposer);
+        data[74..106].copy_from_slice(&self.executed_by);
+        data[106..114].copy_from_slice(&self.created_at.to_le_bytes());
+        data[114..122].copy_from_slice(&self.executed_at.to_le_bytes());
+        data[122..130].copy_from_slice(&self.closed_at.to_le_bytes());
+        data
+    }
+}
+
+/// Layout the program shipped with, before accounts carried a discriminator and a version.
+/// It was not `repr(C)`, so it is declared exactly as it was deployed for the compiler to lay
+/// it out the same way. Voters were stored inline as keys and counted unweighted.
+#[derive(Clone, Copy)]
+struct StreamProposalV0 {
+    proposer: Pubkey,
+    stream_name: [u8; 32],
+    stream_description: [u8; 128],
+    proposal_id: u64,
+    multisig_id: u64,
+    stream_type: u8,
+    created_at: i64,
+    voting_deadline: i64,
+    #[allow(dead_code)]
+    approvals: [Pubkey; 10],
+    approval_count: u8,
+    #[allow(dead_code)]
+    rejections: [Pubkey; 10],
+    rejection_count: u8,
+    total_vote_count: u64,
+    required_threshold: u8,
+    status: u8,
+}
+
+impl StreamProposalV0 {
+    const SPACE: usize = core::mem::size_of::<Self>();
+
+    /// Reads an account still in the original layout. Accounts already carrying the current
+    /// header are up to date; anything else of another size is not a proposal account.
+    fn read(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.starts_with(&StreamProposal::DISCRIMINATOR) {
+            return Err(TreasuryError::AccountUpToDate.into());
+        };
+
+        if data.len() != Self::SPACE {
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        Ok(unsafe { core::ptr::read_unaligned(data.as_ptr() as *const Self) })
+    }
+
+    /// The current proposal, without a payload or vote bitmaps. Original proposals live at
+    /// their legacy address and can only be expired or closed, so their recorded votes are
+    /// kept as unweighted tallies rather than mapped onto member slots; that also keeps the
+    /// result independent of which multisig account the migration is run with.
+    fn upgrade(self) -> StreamProposal {
+        StreamProposal {
+            discriminator: StreamProposal::DISCRIMINATOR,
+            version: StreamProposal::VERSION,
+            proposer: self.proposer,
+            stream_name: self.stream_name,
+            stream_description: self.stream_description,
+            proposal_id: self.proposal_id,
+            multisig_id: self.multisig_id,
+            stream_type: self.stream_type,
+            created_at: self.created_at,
+            voting_deadline: self.voting_deadline,
+            executable_after: 0,
+            executed_by: Pubkey::default(),
+            executed_at: 0,
+            approval_weight: self.approval_count as u64,
+            rejection_weight: self.rejection_count as u64,
+            total_vote_count: self.total_vote_count,
+            required_threshold: self.required_threshold as u64,
+            status: self.status,
+            allow_below_minimum: false,
+            payload_len: 0,
+            vote_bitmap_len: 0,
+        }
+    }
+}
+
+impl AccountHeader for StreamProposal {
+    const DISCRIMINATOR: [u8; 8] = [232, 159, 35, 131, 192, 187, 59, 189];
+    const VERSION: u8 = 1;
 }
 
 impl StreamProposal {
     pub const SIZE: usize = core::mem::size_of::<StreamProposal>();
+    pub const MAX_PAYLOAD_LEN: usize = 640;
+
+    /// Bytes in one vote bitmap covering `member_slots` slots.
+    pub fn bitmap_len(member_slots: usize) -> usize {
+        member_slots.div_ceil(8)
+    }
+
+    pub fn space(payload_len: usize, member_slots: usize) -> usize {
+        Self::SIZE + payload_len + 2 * Self::bitmap_len(member_slots)
+    }
 
     pub fn from_account_info(account: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
             &*(data.as_ptr() as *const Self)
@@ -34,14 +193,210 @@ impl StreamProposal {
     }
 
     pub fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(RefMut::map(account.try_borrow_mut_data()?, |data| unsafe {
             &mut *(data.as_ptr() as *mut Self)
         }))
     }
+
+    /// Upgrades an account still in the original layout to the current one in place,
+    /// resizing it at `payer`'s expense.
+    pub fn migrate(account: &AccountInfo, payer: &AccountInfo) -> ProgramResult {
+        let proposal = Self::original(account)?.upgrade();
+
+        resize_account(account, payer, Self::SIZE)?;
+
+        unsafe { core::ptr::write_unaligned(account.try_borrow_mut_data()?.as_mut_ptr() as *mut Self, proposal) };
+
+        Ok(())
+    }
+
+    /// Proposal id and multisig id of an account still in the original layout, which its
+    /// address was derived from.
+    pub fn original_ids(account: &AccountInfo) -> Result<(u64, u64), ProgramError> {
+        let proposal = Self::original(account)?;
+
+        Ok((proposal.proposal_id, proposal.multisig_id))
+    }
+
+    fn original(account: &AccountInfo) -> Result<StreamProposalV0, ProgramError> {
+        if *account.owner() != crate::ID {
+            return Err(ProgramError::InvalidAccountOwner);
+        };
+
+        StreamProposalV0::read(&account.try_borrow_data()?)
+    }
+
+    /// Tags a freshly created account as `StreamProposal` and returns it for initialization.
+    pub fn initialize(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        write_header::<Self>(account, Self::SIZE)?;
+        Self::from_account_info_mut(account)
+    }
+
+    pub fn payload(account: &AccountInfo) -> Result<Ref<'_, [u8]>, ProgramError> {
+        let payload_len = Self::from_account_info(account)?.payload_len as usize;
+
+        if account.data_len() < Self::SIZE + payload_len {
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        Ok(Ref::map(account.try_borrow_data()?, |data| {
+            &data[Self::SIZE..Self::SIZE + payload_len]
+        }))
+    }
+
+    /// Approval bitmap followed by the rejection bitmap.
+    pub fn votes(account: &AccountInfo) -> Result<Ref<'_, [u8]>, ProgramError> {
+        let (start, end) = Self::from_account_info(account)?.votes_range();
+
+        if account.data_len() < end {
+            return Err(ProgramError::InvalidAccountData);
+        };
+
+        Ok(Ref::map(account.try_borrow_data()?, |data| &data[start..end]))
+    }
+
+    /// Runs `f` on the proposal and its vote bitmaps, borrowed mutably together.
+    pub fn with_votes_mut<R>(
+        account: &AccountInfo,
+        f: impl FnOnce(&mut Self, &mut [u8]) -> Result<R, ProgramError>,
+    ) -> Result<R, ProgramError> {
+        check_account::<Self>(account, Self::SIZE)?;
+
+        let mut data = account.try_borrow_mut_data()?;
+        let (header, rest) = data.split_at_mut(Self::SIZE);
+        let proposal = unsafe { &mut *(header.as_mut_ptr() as *mut Self) };
+
+        let (start, end) = proposal.votes_range();
+        let votes = rest
+            .get_mut(start - Self::SIZE..end - Self::SIZE)
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        f(proposal, votes)
+    }
+
+    fn votes_range(&self) -> (usize, usize) {
+        let start = Self::SIZE + self.payload_len as usize;
+        (start, start + 2 * self.vote_bitmap_len as usize)
+    }
+
+    /// Last moment the proposal may execute, or be voted on. An approved proposal keeps a
+    /// window as long as its voting window once its timelock ends, so a timelock running past
+    /// `voting_deadline` cannot strand it.
+    pub fn execution_deadline(&self) -> i64 {
+        if self.status != ProposalStatus::Approved as u8 {
+            return self.voting_deadline;
+        }
+
+        let window = self.voting_deadline.saturating_sub(self.created_at);
+        self.voting_deadline.max(self.executable_after.saturating_add(window))
+    }
+
+    /// Byte index and mask of `slot` in a bitmap, if the proposal has a bit for it.
+    fn bit(&self, slot: usize) -> Option<(usize, u8)> {
+        (slot / 8 < self.vote_bitmap_len as usize).then(|| (slot / 8, 1 << (slot % 8)))
+    }
+
+    pub fn approved_by(&self, votes: &[u8], slot: usize) -> bool {
+        self.bit(slot).is_some_and(|(byte, mask)| votes[byte] & mask != 0)
+    }
+
+    pub fn rejected_by(&self, votes: &[u8], slot: usize) -> bool {
+        self.bit(slot)
+            .is_some_and(|(byte, mask)| votes[self.vote_bitmap_len as usize + byte] & mask != 0)
+    }
+
+    pub fn has_voted(&self, votes: &[u8], slot: usize) -> bool {
+        self.approved_by(votes, slot) || self.rejected_by(votes, slot)
+    }
+
+    /// Approval and rejection weight of the recorded votes under the current member weights
+    /// and roles; members who lost the voter role no longer count.
+    pub fn tally(&self, votes: &[u8], members: &[Member]) -> (u64, u64) {
+        members.iter().enumerate().fold((0, 0), |(approvals, rejections), (slot, member)| {
+            if self.approved_by(votes, slot) {
+                (approvals.saturating_add(member.voting_weight()), rejections)
+            } else if self.rejected_by(votes, slot) {
+                (approvals, rejections.saturating_add(member.voting_weight()))
+            } else {
+                (approvals, rejections)
+            }
+        })
+    }
+
+    /// Refreshes `approval_weight` and `rejection_weight` from the recorded votes.
+    pub fn retally(&mut self, votes: &[u8], members: &[Member]) {
+        (self.approval_weight, self.rejection_weight) = self.tally(votes, members);
+    }
+
+    /// Records the vote of the member in `slot` and re-tallies. Members added after the
+    /// proposal was created have no bit and cannot vote on it.
+    pub fn record_vote(&mut self, votes: &mut [u8], members: &[Member], slot: usize, approve: bool) -> ProgramResult {
+        let (byte, mask) = self.bit(slot).ok_or(TreasuryError::VoteLimitReached)?;
+
+        if self.has_voted(votes, slot) {
+            return Err(TreasuryError::AlreadyVoted.into());
+        };
+
+        if approve {
+            votes[byte] |= mask;
+        } else {
+            votes[self.vote_bitmap_len as usize + byte] |= mask;
+        }
+
+        self.total_vote_count = self.total_vote_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        self.retally(votes, members);
+
+        Ok(())
+    }
+
+    /// Moves an open proposal whose tally changed to `Rejected` once rejections reach the
+    /// threshold, or to `Approved`, timelocked for `timelock_delay`, once approvals do. While
+    /// an approved proposal is timelocked, rejections matching its approvals veto it.
+    pub fn update_status(&mut self, current_time: i64, timelock_delay: i64) -> ProgramResult {
+        let status = ProposalStatus::try_from(&self.status)?;
+        let in_timelock = status == ProposalStatus::Approved && current_time < self.executable_after;
+        let vetoed = in_timelock && self.rejection_weight >= self.approval_weight;
+
+        if vetoed || self.rejection_weight >= self.required_threshold {
+            self.status = ProposalStatus::Rejected as u8;
+        } else if status == ProposalStatus::Active && self.approval_weight >= self.required_threshold {
+            self.status = ProposalStatus::Approved as u8;
+            self.executable_after = current_time
+                .checked_add(timelock_delay)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Strikes the vote of the member in `slot`, if any, and re-tallies.
+    /// An `Approved` proposal that drops below its threshold goes back to `Active`.
+    pub fn remove_vote(&mut self, votes: &mut [u8], members: &[Member], slot: usize) -> bool {
+        let Some((byte, mask)) = self.bit(slot) else {
+            return false;
+        };
+
+        let rejection_byte = self.vote_bitmap_len as usize + byte;
+
+        if votes[byte] & mask != 0 {
+            votes[byte] &= !mask;
+        } else if votes[rejection_byte] & mask != 0 {
+            votes[rejection_byte] &= !mask;
+        } else {
+            return false;
+        }
+
+        self.total_vote_count = self.total_vote_count.saturating_sub(1);
+        self.retally(votes, members);
+
+        if self.status == ProposalStatus::Approved as u8 && self.approval_weight < self.required_threshold {
+            self.status = ProposalStatus::Active as u8;
+        }
+
+        true
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -50,6 +405,33 @@ pub enum StreamType {
     TokenTransfers,
     PaymentStreaming,
     Vesting,
+    RemoveMember,
+    ConfigChange,
+    MigrateAccount,
+    SetMemberWeight,
+    SetMemberRoles,
+    Unpause,
+    SetGuardian,
+    AddMember,
+    TransferAdmin,
+    SetDepositAllowlist,
+    AllowDepositor,
+    CancelStream,
+}
+
+impl StreamType {
+    /// Stream types a vault reserves a threshold for, including ones not defined yet.
+    pub const MAX_TYPES: usize = 16;
+
+    /// Member role required to create a proposal of this type.
+    pub fn proposer_role(&self) -> u8 {
+        match self {
+            Self::BatchPayments | Self::TokenTransfers | Self::PaymentStreaming | Self::Vesting | Self::CancelStream => Member::TREASURER,
+            Self::RemoveMember | Self::ConfigChange | Self::MigrateAccount | Self::SetMemberWeight | Self::SetMemberRoles
+                | Self::Unpause | Self::SetGuardian | Self::AddMember | Self::TransferAdmin
+                | Self::SetDepositAllowlist | Self::AllowDepositor => Member::PROPOSER,
+        }
+    }
 }
 
 impl TryFrom<&u8> for StreamType {
@@ -61,7 +443,19 @@ impl TryFrom<&u8> for StreamType {
             1 => Ok(StreamType::TokenTransfers),
             2 => Ok(StreamType::PaymentStreaming),
             3 => Ok(StreamType::Vesting),
-            _ => Err(ProgramError::InvalidAccountData)
+            4 => Ok(StreamType::RemoveMember),
+            5 => Ok(StreamType::ConfigChange),
+            6 => Ok(StreamType::MigrateAccount),
+            7 => Ok(StreamType::SetMemberWeight),
+            8 => Ok(StreamType::SetMemberRoles),
+            9 => Ok(StreamType::Unpause),
+            10 => Ok(StreamType::SetGuardian),
+            11 => Ok(StreamType::AddMember),
+            12 => Ok(StreamType::TransferAdmin),
+            13 => Ok(StreamType::SetDepositAllowlist),
+            14 => Ok(StreamType::AllowDepositor),
+            15 => Ok(StreamType::CancelStream),
+            _ => Err(TreasuryError::InvalidStreamType.into())
         }
     }
 }
@@ -105,7 +499,190 @@ impl TryFrom<&u8> for ProposalStatus {
             12 => Ok(ProposalStatus::Paused),
             13 => Ok(ProposalStatus::Disputed),
             14 => Ok(ProposalStatus::RequiresUpdate),
-            _ => Err(ProgramError::InvalidAccountData)
+            _ => Err(TreasuryError::InvalidProposalStatus.into())
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    const SLOT_1: usize = 0;
+    const SLOT_2: usize = 1;
+    const SLOT_3: usize = 9;
+
+    fn members() -> Vec<Member> {
+        let mut members = vec![Member::new([7u8; 32], 1, Member::ALL_ROLES); 12];
+        members[SLOT_1].weight = 3;
+        members[SLOT_3].weight = 2;
+        members
+    }
+
+    fn approved_proposal() -> (StreamProposal, Vec<u8>) {
+        let members = members();
+        let mut proposal = StreamProposal {
+            discriminator: StreamProposal::DISCRIMINATOR,
+            version: StreamProposal::VERSION,
+            proposer: [1u8; 32],
+            stream_name: [0u8; 32],
+            stream_description: [0u8; 128],
+            proposal_id: 1,
+            multisig_id: 1,
+            stream_type: StreamType::TokenTransfers as u8,
+            created_at: 0,
+            voting_deadline: 100,
+            executable_after: 0,
+            executed_by: Pubkey::default(),
+            executed_at: 0,
+            approval_weight: 0,
+            rejection_weight: 0,
+            total_vote_count: 0,
+            required_threshold: 4,
+            status: ProposalStatus::Approved as u8,
+            allow_below_minimum: false,
+            payload_len: 0,
+            vote_bitmap_len: StreamProposal::bitmap_len(members.len()) as u16,
+        };
+        let mut votes = vec![0u8; 2 * proposal.vote_bitmap_len as usize];
+
+        proposal.record_vote(&mut votes, &members, SLOT_1, true).unwrap();
+        proposal.record_vote(&mut votes, &members, SLOT_2, true).unwrap();
+        proposal.record_vote(&mut votes, &members, SLOT_3, false).unwrap();
+
+        (proposal, votes)
+    }
+
+    #[test]
+    fn test_record_vote_tallies_weight_and_rejects_double_votes() {
+        let (mut proposal, mut votes) = approved_proposal();
+
+        assert_eq!(proposal.approval_weight, 4);
+        assert_eq!(proposal.rejection_weight, 2);
+        assert_eq!(proposal.total_vote_count, 3);
+        assert!(proposal.approved_by(&votes, SLOT_2));
+        assert!(proposal.rejected_by(&votes, SLOT_3));
+
+        assert_eq!(
+            proposal.record_vote(&mut votes, &members(), SLOT_3, true),
+            Err(TreasuryError::AlreadyVoted.into())
+        );
+        assert_eq!(
+            proposal.record_vote(&mut votes, &members(), 16, true),
+            Err(TreasuryError::VoteLimitReached.into())
+        );
+    }
+
+    #[test]
+    fn test_tally_uses_current_weights() {
+        let (proposal, votes) = approved_proposal();
+        let mut members = members();
+        members[SLOT_1].weight = 1;
+
+        assert_eq!(proposal.tally(&votes, &members), (2, 2));
+    }
+
+    #[test]
+    fn test_remove_vote_clears_approval_and_reverts_status() {
+        let (mut proposal, mut votes) = approved_proposal();
+
+        assert!(proposal.remove_vote(&mut votes, &members(), SLOT_2));
+        assert!(proposal.approved_by(&votes, SLOT_1));
+        assert_eq!(proposal.approval_weight, 3);
+        assert_eq!(proposal.total_vote_count, 2);
+        assert_eq!(proposal.status, ProposalStatus::Active as u8);
+        assert!(!proposal.has_voted(&votes, SLOT_2));
+    }
+
+    #[test]
+    fn test_remove_vote_from_rejections() {
+        let (mut proposal, mut votes) = approved_proposal();
+
+        assert!(proposal.remove_vote(&mut votes, &members(), SLOT_3));
+        assert_eq!(proposal.rejection_weight, 0);
+        assert!(!proposal.has_voted(&votes, SLOT_3));
+        assert_eq!(proposal.status, ProposalStatus::Approved as u8);
+        assert!(!proposal.remove_vote(&mut votes, &members(), SLOT_3));
+    }
+
+    #[test]
+    fn test_proposal_record_bytes() {
+        let (mut proposal, _) = approved_proposal();
+        proposal.status = ProposalStatus::Executed as u8;
+        proposal.executed_by = [7u8; 32];
+        proposal.executed_at = 90;
+
+        let data = ProposalRecord::new(&proposal, 120).to_bytes();
+        assert_eq!(data[8..16], 1u64.to_le_bytes());
+        assert_eq!(data[17], ProposalStatus::Executed as u8);
+        assert_eq!(data[18..26], 4u64.to_le_bytes());
+        assert_eq!(data[74..106], [7u8; 32]);
+        assert_eq!(data[114..122], 90i64.to_le_bytes());
+        assert_eq!(data[122..130], 120i64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_update_status_timelocks_approval_and_allows_veto() {
+        let (mut proposal, mut votes) = approved_proposal();
+
+        proposal.remove_vote(&mut votes, &members(), SLOT_2);
+        proposal.record_vote(&mut votes, &members(), SLOT_2, true).unwrap();
+        proposal.update_status(50, 10).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Approved as u8);
+        assert_eq!(proposal.executable_after, 60);
+
+        // Hand-set tallies: rejections match approvals, and the threshold is out of their reach
+        // so only a veto can reject. A veto only applies while the timelock is running.
+        proposal.required_threshold = 10;
+        proposal.rejection_weight = proposal.approval_weight;
+        proposal.update_status(70, 10).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Approved as u8);
+
+        proposal.update_status(55, 10).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Rejected as u8);
+    }
+
+    #[test]
+    fn test_upgrades_original_layout_keeping_unweighted_tallies() {
+        let mut old: StreamProposalV0 = unsafe { core::mem::zeroed() };
+        old.proposer = [1u8; 32];
+        old.proposal_id = 4;
+        old.multisig_id = 7;
+        old.stream_type = StreamType::TokenTransfers as u8;
+        old.voting_deadline = 100;
+        old.approvals[..2].copy_from_slice(&[[1u8; 32], [2u8; 32]]);
+        old.approval_count = 2;
+        old.rejections[0] = [3u8; 32];
+        old.rejection_count = 1;
+        old.total_vote_count = 3;
+        old.required_threshold = 2;
+        old.status = ProposalStatus::Approved as u8;
+
+        let data = unsafe { core::slice::from_raw_parts(&old as *const StreamProposalV0 as *const u8, StreamProposalV0::SPACE) };
+        let proposal = StreamProposalV0::read(data).unwrap().upgrade();
+
+        assert_eq!((proposal.discriminator, proposal.version), (StreamProposal::DISCRIMINATOR, StreamProposal::VERSION));
+        assert_eq!((proposal.proposer, proposal.proposal_id, proposal.multisig_id), ([1u8; 32], 4, 7));
+        assert_eq!((proposal.approval_weight, proposal.rejection_weight, proposal.required_threshold), (2, 1, 2));
+        assert_eq!((proposal.payload_len, proposal.vote_bitmap_len), (0, 0));
+        assert_eq!(proposal.status, ProposalStatus::Approved as u8);
+        assert_eq!(proposal.execution_deadline(), 100);
+
+        let mut current = vec![0u8; StreamProposal::SIZE];
+        current[..8].copy_from_slice(&StreamProposal::DISCRIMINATOR);
+        assert_eq!(StreamProposalV0::read(&current).err(), Some(TreasuryError::AccountUpToDate.into()));
+    }
+
+    #[test]
+    fn test_approval_close_to_deadline_stays_executable_after_timelock() {
+        let (mut proposal, mut votes) = approved_proposal();
+
+        proposal.remove_vote(&mut votes, &members(), SLOT_2);
+        assert_eq!(proposal.execution_deadline(), 100);
+
+        proposal.record_vote(&mut votes, &members(), SLOT_2, true).unwrap();
+        proposal.update_status(95, 20).unwrap();
+        assert_eq!(proposal.executable_after, 115);
+        assert_eq!(proposal.execution_deadline(), 215);
+    }
+}
@@ -1,8 +1,13 @@
 use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError, pubkey::Pubkey, *};
 use shank::ShankAccount;
 
+use crate::states::{check_account, write_header, AccountHeader};
+
 #[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+#[repr(C)]
 pub struct VestingContractInfo {
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub creator: Pubkey,
     pub company_name: [u8; 32],
     pub mint: Pubkey,
@@ -20,13 +25,16 @@ pub struct VestingContractInfo {
     pub fully_claimed: bool,
 }
 
+impl AccountHeader for VestingContractInfo {
+    const DISCRIMINATOR: [u8; 8] = [143, 199, 250, 152, 34, 166, 77, 5];
+    const VERSION: u8 = 1;
+}
+
 impl VestingContractInfo {
     pub const SIZE: usize = core::mem::size_of::<VestingContractInfo>();
 
     pub fn from_account_info(account: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
             &*(data.as_ptr() as *const Self)
@@ -34,12 +42,16 @@ impl VestingContractInfo {
     }
 
     pub fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(RefMut::map(account.try_borrow_mut_data()?, |data| unsafe {
             &mut *(data.as_ptr() as *mut Self)
         }))
     }
+
+    /// Tags a freshly created account as `VestingContractInfo` and returns it for initialization.
+    pub fn initialize(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        write_header::<Self>(account, Self::SIZE)?;
+        Self::from_account_info_mut(account)
+    }
 }
\ No newline at end of file
@@ -1,8 +1,13 @@
 use pinocchio::{account_info::{AccountInfo, Ref, RefMut}, program_error::ProgramError, pubkey::Pubkey, *};
 use shank::ShankAccount;
 
+use crate::states::{check_account, write_header, AccountHeader};
+
 #[derive(Debug, Clone, Copy, PartialEq, ShankAccount)]
+#[repr(C)]
 pub struct VestingRecordInfo {
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub beneficiary: Pubkey,
     pub mint: Pubkey,
     pub total_vested_tokens: u64,
@@ -17,13 +22,16 @@ pub struct VestingRecordInfo {
     pub end_time: i64,
 }
 
+impl AccountHeader for VestingRecordInfo {
+    const DISCRIMINATOR: [u8; 8] = [106, 99, 91, 251, 65, 124, 139, 240];
+    const VERSION: u8 = 1;
+}
+
 impl VestingRecordInfo {
     pub const SIZE: usize = core::mem::size_of::<VestingRecordInfo>();
 
     pub fn from_account_info(account: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(Ref::map(account.try_borrow_data()?, |data| unsafe {
             &*(data.as_ptr() as *const Self)
@@ -31,12 +39,16 @@ impl VestingRecordInfo {
     }
 
     pub fn from_account_info_mut(account: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
-        if account.data_len() < Self::SIZE {
-            return Err(ProgramError::InvalidAccountData);
-        };
+        check_account::<Self>(account, Self::SIZE)?;
 
         Ok(RefMut::map(account.try_borrow_mut_data()?, |data| unsafe {
             &mut *(data.as_ptr() as *mut Self)
         }))
     }
+
+    /// Tags a freshly created account as `VestingRecordInfo` and returns it for initialization.
+    pub fn initialize(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        write_header::<Self>(account, Self::SIZE)?;
+        Self::from_account_info_mut(account)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,45 @@
+use multi_signature_treasury_management::instructions::MultiSignatureInstructions;
+use serde_json::Value;
+use shank_idl::{extract_idl, ParseIdlOpts};
+
+const IDL_PATH: &str = "idl/multi_signature_treasury_management.json";
+
+fn generated_idl() -> Value {
+    let idl = extract_idl("src/lib.rs", ParseIdlOpts::default())
+        .expect("failed to parse the program for its IDL")
+        .expect("no IDL extracted");
+
+    serde_json::from_str(&idl.try_into_json().unwrap()).unwrap()
+}
+
+fn committed_idl() -> Value {
+    serde_json::from_str(&std::fs::read_to_string(IDL_PATH).unwrap()).unwrap()
+}
+
+#[test]
+fn test_committed_idl_is_up_to_date() {
+    assert!(
+        generated_idl() == committed_idl(),
+        "{IDL_PATH} is stale, regenerate it with `shank idl -o idl`"
+    );
+}
+
+#[test]
+fn test_idl_instructions_match_dispatcher() {
+    let idl = committed_idl();
+    let instructions = idl["instructions"].as_array().unwrap();
+
+    for instruction in instructions {
+        let name = instruction["name"].as_str().unwrap();
+        let discriminant = instruction["discriminant"]["value"].as_u64().unwrap() as u8;
+
+        let dispatched = MultiSignatureInstructions::try_from(&discriminant)
+            .unwrap_or_else(|_| panic!("IDL instruction {name} ({discriminant}) is not dispatched"));
+        assert_eq!(format!("{dispatched:?}"), name);
+    }
+
+    let dispatched_count = (0..=u8::MAX)
+        .filter(|discriminant| MultiSignatureInstructions::try_from(discriminant).is_ok())
+        .count();
+    assert_eq!(dispatched_count, instructions.len(), "dispatcher has instructions missing from the IDL");
+}
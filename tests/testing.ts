@@ -77,9 +77,9 @@ describe("Multi Signature Vault", function() {
 
             [treasuryVaultPda] = PublicKey.findProgramAddressSync(
                 [
-                    Buffer.from("multisig_vault"), 
-                    mint.toBuffer(), 
-                    MULTISIG_ID.toBuffer("le", 8)
+                    Buffer.from("multisig_vault"),
+                    multisigInfoPda.toBuffer(),
+                    mint.toBuffer()
                 ],
                 program.programId
             );
@@ -87,8 +87,8 @@ describe("Multi Signature Vault", function() {
             [streamProposalAccount] = PublicKey.findProgramAddressSync(
                 [
                     Buffer.from("stream_proposal"),
-                    PROPOSAL_ID.toBuffer("le", 8),
-                    MULTISIG_ID.toBuffer("le", 8)
+                    multisigInfoPda.toBuffer(),
+                    PROPOSAL_ID.toBuffer("le", 8)
                 ],
                 program.programId
             )
@@ -460,13 +460,20 @@ describe("Multi Signature Vault", function() {
         this.timeout(60000); 
         let AMOUNT = new BN(1);
 
+        const [depositReceiptPda] = PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("deposit_receipt"),
+                multisigInfoPda.toBuffer(),
+                provider.wallet.publicKey.toBuffer(),
+            ],
+            program.programId
+        );
+
         const instructionDiscriminant = Buffer.from([4]); 
-        const multisigIdBuffer = MULTISIG_ID.toBuffer("le", 8);
         const amountBuffer = AMOUNT.toBuffer("le", 8);
 
         let instructionData = Buffer.concat([
             instructionDiscriminant,
-            multisigIdBuffer,
             amountBuffer
         ]);
 
@@ -475,7 +482,7 @@ describe("Multi Signature Vault", function() {
             new TransactionInstruction({
                 keys: [
                     {
-                        pubkey: provider.wallet.publicKey, // admin
+                        pubkey: provider.wallet.publicKey, // depositor
                         isSigner: true,
                         isWritable: true,
                     },
@@ -499,6 +506,11 @@ describe("Multi Signature Vault", function() {
                         isSigner: false,
                         isWritable: true,
                     },
+                    {
+                        pubkey: depositReceiptPda, // deposit_receipt
+                        isSigner: false,
+                        isWritable: true,
+                    },
                     {
                         pubkey: PINOCCHIO_TOKEN_PROGRAM_ID, // token_program
                         isSigner: false,